@@ -1,4 +1,6 @@
-use num_traits::ops::wrapping::WrappingAdd;
+use core::ops::Shr;
+
+use num_traits::{ops::wrapping::WrappingAdd, Bounded, CheckedAdd, Zero};
 
 /// Wrapping Accumulator
 #[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
@@ -25,3 +27,188 @@ where
         Some(s)
     }
 }
+
+/// Saturating Accumulator
+///
+/// Like [`Accu`] but saturates at the numeric bounds instead of wrapping.
+/// A sticky flag records that saturation occurred.
+///
+/// ```
+/// # use idsp::SaturatingAccu;
+/// let mut a = SaturatingAccu::new(100i8, 20);
+/// let y: Vec<_> = a.by_ref().take(4).collect();
+/// assert_eq!(y, [100, 120, 127, 127]);
+/// assert!(a.overflow());
+/// a.clear();
+/// assert!(!a.overflow());
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct SaturatingAccu<T> {
+    state: T,
+    step: T,
+    overflow: bool,
+}
+
+impl<T> SaturatingAccu<T> {
+    /// Create a new accumulator with given initial state and step.
+    pub fn new(state: T, step: T) -> Self {
+        Self {
+            state,
+            step,
+            overflow: false,
+        }
+    }
+
+    /// Whether the accumulator has saturated since the last `clear()`
+    pub fn overflow(&self) -> bool {
+        self.overflow
+    }
+
+    /// Clear the overflow flag.
+    pub fn clear(&mut self) {
+        self.overflow = false;
+    }
+}
+
+impl<T> SaturatingAccu<T>
+where
+    T: CheckedAdd + Bounded + Zero + PartialOrd + Copy,
+{
+    /// Add a value to the accumulator and return the new state.
+    pub fn add(&mut self, x: T) -> T {
+        self.state = match self.state.checked_add(&x) {
+            Some(s) => s,
+            None => {
+                self.overflow = true;
+                if x > T::zero() {
+                    T::max_value()
+                } else {
+                    T::min_value()
+                }
+            }
+        };
+        self.state
+    }
+}
+
+impl<T> Iterator for SaturatingAccu<T>
+where
+    T: CheckedAdd + Bounded + Zero + PartialOrd + Copy,
+{
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        let s = self.state;
+        self.add(self.step);
+        Some(s)
+    }
+}
+
+/// Frequency modulated wrapping Accumulator
+///
+/// Like [`Accu`] but the step (frequency) is modulated each sample by
+/// a scaled modulation input: the effective step is `step + (m >> shift)`.
+/// This is a phase accumulator for FM direct digital synthesis.
+///
+/// ```
+/// # use idsp::FmAccu;
+/// let mut a = FmAccu::new(0i32, 100);
+/// let y: Vec<_> = [0, 64, -64, 0].iter().map(|m| a.update(*m, 4)).collect();
+/// assert_eq!(y, [0, 100, 204, 300]);
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct FmAccu<T> {
+    state: T,
+    step: T,
+}
+
+impl<T: Copy> FmAccu<T> {
+    /// Create a new accumulator with given initial state and step.
+    pub fn new(state: T, step: T) -> Self {
+        Self { state, step }
+    }
+
+    /// The unmodulated step
+    pub fn step(&self) -> T {
+        self.step
+    }
+
+    /// Set the unmodulated step.
+    pub fn set_step(&mut self, step: T) {
+        self.step = step;
+    }
+}
+
+impl<T> FmAccu<T>
+where
+    T: WrappingAdd + Shr<u32, Output = T> + Copy,
+{
+    /// Return the current state and advance by the modulated step.
+    ///
+    /// # Args
+    /// * `m`: Modulation input
+    /// * `shift`: Modulation scaling (right shift)
+    pub fn update(&mut self, m: T, shift: u32) -> T {
+        let s = self.state;
+        self.state = s.wrapping_add(&self.step).wrapping_add(&(m >> shift));
+        s
+    }
+}
+
+/// Wide integrator
+///
+/// Totalizes `i64` samples in an `i128` accumulator. This does not overflow
+/// for at least `1 << 63` full scale samples and does not lose
+/// precision, e.g. for frequency counting over long intervals.
+///
+/// ```
+/// # use idsp::WideIntegrator;
+/// let mut i = WideIntegrator::default();
+/// for _ in 0..4 {
+///     i.update(i64::MAX);
+/// }
+/// assert_eq!(i.get(), 4 * i64::MAX as i128);
+/// assert_eq!(i.count(), 4);
+/// assert_eq!(i.mean(), i64::MAX);
+/// assert_eq!(i.take(), 4 * i64::MAX as i128);
+/// assert_eq!(i.get(), 0);
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+pub struct WideIntegrator {
+    sum: i128,
+    count: u64,
+}
+
+impl WideIntegrator {
+    /// Add a sample and return the new sum.
+    pub fn update(&mut self, x: i64) -> i128 {
+        self.sum += x as i128;
+        self.count += 1;
+        self.sum
+    }
+
+    /// The current sum
+    pub fn get(&self) -> i128 {
+        self.sum
+    }
+
+    /// The number of samples
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The mean of the samples (rounded towards zero), zero if there are none
+    pub fn mean(&self) -> i64 {
+        if self.count == 0 {
+            0
+        } else {
+            (self.sum / self.count as i128) as _
+        }
+    }
+
+    /// Return the sum and reset.
+    pub fn take(&mut self) -> i128 {
+        let sum = self.sum;
+        *self = Self::default();
+        sum
+    }
+}
@@ -0,0 +1,254 @@
+use num_traits::Float;
+
+use crate::Delay;
+
+/// Least mean squares (LMS) adaptive FIR filter
+///
+/// Adapts the `N` taps of an FIR filter such that its output
+/// tracks a desired signal, e.g. for system identification, noise
+/// and echo cancellation.
+///
+/// Two update rules share the same state:
+/// * [`Lms::update()`]: Plain LMS, the adaptation rate scales with the input power.
+/// * [`Lms::update_normalized()`]: Normalized LMS (NLMS), the step size is
+///   normalized by the input energy in the filter window for robust
+///   convergence independent of the input amplitude.
+///
+/// The taps are kept with 62 fractional bits, [`Lms::taps()`] returns them
+/// in [`crate::Coefficient`] scaling (`1 << 30`).
+///
+/// ```
+/// # use idsp::{Delay, Lms, Xoshiro128};
+/// // Unknown plant to identify
+/// let h = [1 << 29, -1 << 28, 1 << 27];
+/// let mut plant = Delay::<i32, 3>::default();
+/// let mut lms = Lms::<4>::default();
+/// let mut rng = Xoshiro128::new(1);
+/// for _ in 0..1000 {
+///     // Input amplitude 1/16 full scale
+///     let x = rng.update() >> 4;
+///     plant.push(x);
+///     let d = plant.iter().zip(h).map(|(x, h)| (*x as i64 * h as i64) >> 30).sum::<i64>();
+///     lms.update_normalized(x, d as i32, 1 << 31);
+/// }
+/// let w = lms.taps();
+/// for (w, h) in w.iter().zip(h.iter().chain([0].iter())) {
+///     assert!((w - h).abs() < 1 << 12);
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Lms<const N: usize> {
+    /// Input history, newest first
+    x: Delay<i32, N>,
+    /// Taps with 62 fractional bits
+    w: [i64; N],
+    /// Energy of the input history
+    energy: i128,
+}
+
+impl<const N: usize> Default for Lms<N> {
+    fn default() -> Self {
+        Self {
+            x: Delay::default(),
+            w: [0; N],
+            energy: 0,
+        }
+    }
+}
+
+impl<const N: usize> Lms<N> {
+    fn filter(&mut self, x: i32) -> i32 {
+        let x0 = self.x.push(x) as i128;
+        self.energy += x as i128 * x as i128 - x0 * x0;
+        let y = self
+            .w
+            .iter()
+            .zip(self.x.iter())
+            .map(|(w, x)| *w as i128 * *x as i128)
+            .sum::<i128>()
+            >> 62;
+        y.clamp(i32::MIN as _, i32::MAX as _) as _
+    }
+
+    fn adapt(&mut self, e: i32, mu: i128) {
+        for (w, x) in self.w.iter_mut().zip(self.x.iter()) {
+            *w = w.saturating_add(((mu * e as i128 * *x as i128) >> 32) as i64);
+        }
+    }
+
+    /// Ingest a new sample and adapt with the plain LMS rule.
+    ///
+    /// `w += mu*e*x` with the input `x` and the error `e` as fractions of
+    /// full scale. For stability `mu*N*E[x**2] < 1` is required.
+    ///
+    /// # Args
+    /// * `x`: Input sample
+    /// * `d`: Desired output
+    /// * `mu`: Step size in units of `1 << 32`
+    ///
+    /// # Returns
+    /// The filter output before adaptation and the error `d - y`
+    pub fn update(&mut self, x: i32, d: i32, mu: u32) -> (i32, i32) {
+        let y = self.filter(x);
+        let e = d.saturating_sub(y);
+        self.adapt(e, mu as _);
+        (y, e)
+    }
+
+    /// Ingest a new sample and adapt with the normalized LMS rule.
+    ///
+    /// `w += mu*e*x/|x|**2` where `|x|**2` is the energy of the input
+    /// in the filter window. Stable for all `mu`.
+    ///
+    /// # Args
+    /// * `x`: Input sample
+    /// * `d`: Desired output
+    /// * `mu`: Step size in units of `1 << 32`
+    ///
+    /// # Returns
+    /// The filter output before adaptation and the error `d - y`
+    pub fn update_normalized(&mut self, x: i32, d: i32, mu: u32) -> (i32, i32) {
+        let y = self.filter(x);
+        let e = d.saturating_sub(y);
+        // Regularize to bound the step for small inputs
+        let energy = self.energy + (1 << 32);
+        self.adapt(e, ((mu as i128) << 62) / energy);
+        (y, e)
+    }
+
+    /// The filter taps in units of `1 << 30`
+    pub fn taps(&self) -> [i32; N] {
+        self.w.map(|w| (w >> 32) as _)
+    }
+
+    /// Reset the taps to zero.
+    pub fn reset(&mut self) {
+        self.w = [0; N];
+    }
+}
+
+/// Recursive least squares (RLS) adaptive FIR filter
+///
+/// Adapts the `N` taps of an FIR filter to minimize the exponentially
+/// weighted squared error between its output and a desired signal.
+/// Converges much faster than [`Lms`] at `O(N**2)` cost per sample.
+///
+/// The `N x N` inverse correlation matrix storage is provided by the caller.
+///
+/// ```
+/// # use idsp::Rls;
+/// let h = [0.5, -0.25, 0.125];
+/// let mut p = [[0.0; 3]; 3];
+/// let mut rls = Rls::new(&mut p, 100.0);
+/// let mut x = [0.0f64; 3];
+/// for i in 0..20 {
+///     x.rotate_right(1);
+///     x[0] = ((i * 7 % 11) as f64 - 5.0) / 5.0;
+///     let d = x.iter().zip(h).map(|(x, h)| x * h).sum();
+///     rls.update(x[0], d, 0.99);
+/// }
+/// for (w, h) in rls.taps().iter().zip(h) {
+///     assert!((w - h).abs() < 1e-3);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Rls<'a, F, const N: usize> {
+    /// Inverse input correlation matrix
+    p: &'a mut [[F; N]; N],
+    /// Input history, newest first
+    x: [F; N],
+    /// Taps
+    w: [F; N],
+}
+
+impl<'a, F: Float, const N: usize> Rls<'a, F, N> {
+    /// Create a new filter.
+    ///
+    /// # Args
+    /// * `p`: Inverse correlation matrix storage
+    /// * `delta`: Initial inverse correlation (diagonal), large for fast initial
+    ///   convergence, e.g. `100/E[x**2]`
+    pub fn new(p: &'a mut [[F; N]; N], delta: F) -> Self {
+        let mut rls = Self {
+            p,
+            x: [F::zero(); N],
+            w: [F::zero(); N],
+        };
+        rls.reset(delta);
+        rls
+    }
+
+    /// Ingest a new sample and adapt.
+    ///
+    /// # Args
+    /// * `x`: Input sample
+    /// * `d`: Desired output
+    /// * `lambda`: Forgetting factor, `0 < lambda <= 1`, typically `0.95..1`
+    ///
+    /// # Returns
+    /// The filter output before adaptation and the error `d - y`
+    pub fn update(&mut self, x: F, d: F, lambda: F) -> (F, F) {
+        self.x.rotate_right(1);
+        self.x[0] = x;
+        let y = dot(&self.w, &self.x);
+        let e = d - y;
+        let px = self.p.map(|p| dot(&p, &self.x));
+        let g = F::one() / (lambda + dot(&self.x, &px));
+        for (w, px) in self.w.iter_mut().zip(px.iter()) {
+            *w = *w + *px * g * e;
+        }
+        let lambda = F::one() / lambda;
+        for (p, pxi) in self.p.iter_mut().zip(px.iter()) {
+            for (p, pxj) in p.iter_mut().zip(px.iter()) {
+                *p = (*p - *pxi * *pxj * g) * lambda;
+            }
+        }
+        (y, e)
+    }
+
+    /// The filter taps
+    pub fn taps(&self) -> &[F; N] {
+        &self.w
+    }
+
+    /// Reset the taps to zero and the inverse correlation matrix to
+    /// `delta` times the identity.
+    pub fn reset(&mut self, delta: F) {
+        for (i, p) in self.p.iter_mut().enumerate() {
+            for (j, p) in p.iter_mut().enumerate() {
+                *p = if i == j { delta } else { F::zero() };
+            }
+        }
+        self.w = [F::zero(); N];
+    }
+}
+
+fn dot<F: Float, const N: usize>(a: &[F; N], b: &[F; N]) -> F {
+    a.iter()
+        .zip(b.iter())
+        .fold(F::zero(), |s, (a, b)| s + *a * *b)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Xoshiro128;
+
+    #[test]
+    fn lms_identify() {
+        let h = [-3 << 27, 1 << 28];
+        let mut lms = Lms::<2>::default();
+        let mut rng = Xoshiro128::new(2);
+        let mut x1 = 0;
+        for _ in 0..20_000 {
+            let x = rng.update() >> 2;
+            let d = (x as i64 * h[0] as i64 + x1 as i64 * h[1] as i64) >> 30;
+            x1 = x;
+            lms.update(x, d as i32, 1 << 30);
+        }
+        let w = lms.taps();
+        for (w, h) in w.iter().zip(h.iter()) {
+            assert!((w - h).abs() < 1 << 12, "{w} {h}");
+        }
+    }
+}
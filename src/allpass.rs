@@ -0,0 +1,123 @@
+use crate::Filter;
+
+/// Design Thiran all-pass fractional delay coefficients
+///
+/// The all-pass filter of order `N` has a maximally flat group delay
+/// of `delay` samples at DC.
+/// For stability and good accuracy use `N - 0.5 <= delay <= N + 0.5`.
+///
+/// # Args
+/// * `delay`: Delay in samples
+///
+/// # Returns
+/// Denominator coefficients `[a1, ..., aN]` (the leading `a0 = 1` omitted).
+/// Apply with [`Thiran`] after quantizing with
+/// [`crate::Coefficient::quantize()`].
+///
+/// ```
+/// # use idsp::thiran;
+/// let a: [f64; 1] = thiran(0.5);
+/// assert!((a[0] - 1.0 / 3.0).abs() < 1e-12);
+/// ```
+pub fn thiran<const N: usize>(delay: f64) -> [f64; N] {
+    let mut a = [0.0; N];
+    let mut binom = 1.0;
+    for (k, a) in (1..=N).zip(a.iter_mut()) {
+        binom *= (N + 1 - k) as f64 / k as f64;
+        let p: f64 = (0..=N)
+            .map(|n| (delay - N as f64 + n as f64) / (delay - N as f64 + (k + n) as f64))
+            .product();
+        *a = if k & 1 == 1 { -binom } else { binom } * p;
+    }
+    a
+}
+
+/// Thiran all-pass fractional delay filter
+///
+/// `y0 = x[-N] + sum(a[k]*(x[k - N] - y[-k]) for k in 1..=N)`
+///
+/// An all-pass filter of order `N` with unity magnitude response and
+/// maximally flat group delay. Use it to match sub-sample latencies between
+/// parallel paths without amplitude distortion.
+/// The coefficients are typically designed with [`thiran()`].
+/// They use the [`crate::Coefficient`] scaling (`1 << 30` is unity) and
+/// must have magnitude less than unity.
+///
+/// ```
+/// # use idsp::{thiran, Coefficient, Filter, Thiran};
+/// let a: [f64; 2] = thiran(1.8);
+/// let a = a.map(i32::quantize);
+/// let mut t = Thiran::<2>::default();
+/// let y: Vec<_> = (0..10).map(|i| t.update(i << 20, &a) >> 10).collect();
+/// assert_eq!(y[7..], [5324, 6348, 7372]); // (i - 1.8) << 10, truncated
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Thiran<const N: usize> {
+    /// Previous inputs, newest first
+    x: [i32; N],
+    /// Previous outputs, newest first
+    y: [i32; N],
+}
+
+impl<const N: usize> Default for Thiran<N> {
+    fn default() -> Self {
+        Self {
+            x: [0; N],
+            y: [0; N],
+        }
+    }
+}
+
+impl<const N: usize> Filter for Thiran<N> {
+    /// Denominator coefficients `[a1, ..., aN]`
+    type Config = [i32; N];
+
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        let xn = if N == 0 { x } else { self.x[N - 1] };
+        let mut s = (xn as i64) << 30;
+        for (i, a) in k.iter().enumerate() {
+            let xk = if i + 1 == N { x } else { self.x[N - 2 - i] };
+            s += *a as i64 * (xk as i64 - self.y[i] as i64);
+        }
+        let y = (s >> 30).clamp(i32::MIN as _, i32::MAX as _) as i32;
+        if N > 0 {
+            self.x.copy_within(..N - 1, 1);
+            self.x[0] = x;
+            self.y.copy_within(..N - 1, 1);
+            self.y[0] = y;
+        }
+        y
+    }
+
+    fn get(&self) -> i32 {
+        self.y.first().copied().unwrap_or_default()
+    }
+
+    fn set(&mut self, x: i32) {
+        self.x = [x; N];
+        self.y = [x; N];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Coefficient;
+
+    #[test]
+    fn delay() {
+        for d in [0.6, 1.0, 1.3] {
+            let a: [f64; 1] = thiran(d);
+            let a = a.map(i32::quantize);
+            let mut t = Thiran::<1>::default();
+            let w = 0.01;
+            for i in 0..1000 {
+                let y = t.update((1e9 * (w * i as f64).sin()) as i32, &a);
+                if i > 100 {
+                    let y0 = 1e9 * (w * (i as f64 - d)).sin();
+                    assert!((y as f64 - y0).abs() < 1e3, "{d} {i} {y} {y0}");
+                }
+            }
+        }
+    }
+}
@@ -4,11 +4,8 @@ fn divi(mut y: u32, mut x: u32) -> u32 {
     y <<= z;
     x += (1 << (15 - z)) - 1;
     x >>= 16 - z;
-    if x == 0 {
-        0 // x == y == 0
-    } else {
-        ((y / x) << 15) + (1 << 14)
-    }
+    // x == y == 0 yields 0
+    y.checked_div(x).map_or(0, |r| (r << 15) + (1 << 14))
 }
 
 fn atani(x: u32) -> u32 {
@@ -100,8 +97,8 @@ mod tests {
         const N: usize = 321;
         let mut test_vals = [0i32; N + 2];
         let scale = (1i64 << 31) as f64;
-        for i in 0..N {
-            test_vals[i] = (scale * (-1. + 2. * i as f64 / N as f64)) as i32;
+        for (i, x) in test_vals.iter_mut().take(N).enumerate() {
+            *x = (scale * (-1. + 2. * i as f64 / N as f64)) as i32;
         }
 
         assert!(test_vals.contains(&i32::MIN));
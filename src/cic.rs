@@ -0,0 +1,437 @@
+use num_traits::Float;
+
+use crate::{solve, Filter};
+
+/// Cascaded integrator comb decimator
+///
+/// Order `N` (number of integrator and comb stages), differential delay one,
+/// and runtime configurable decimation rate `R`.
+///
+/// The transfer function is that of `N` cascaded moving averages (boxcars)
+/// of length `R` followed by decimation by `R`:
+/// `H(z) = ((1 - z^-R)/(1 - z^-1))^N`.
+///
+/// # Bit growth and pruning
+///
+/// The DC gain is `R^N` ([`CicDecimator::gain()`]). The output word grows by
+/// `N*log2(R)` bits with respect to the input word.
+/// Integrators and combs are wrapping `i64`. Due to the modular arithmetic
+/// the result is correct as long as the output (not the intermediate
+/// integrator values) fits: `32 + N*ceil(log2(R)) <= 64`.
+/// E.g. order 4 supports rates up to `1 << 8`.
+///
+/// The output is not rescaled. To obtain an `i32` result with unity gain
+/// for power-of-two rates, shift right by `N*log2(R)`
+/// ([`CicDecimator::gain_log2()`]).
+/// Following Hogenauer, the lowest bits of the later stages could be
+/// pruned (truncated) with only marginal noise penalty; with `i64` stages this
+/// is not necessary.
+///
+/// ```
+/// # use idsp::CicDecimator;
+/// let mut c = CicDecimator::<3>::new(4);
+/// let y: Vec<_> = (0..16).filter_map(|_| c.update(1)).collect();
+/// assert_eq!(y, [20, 60, 64, 64]);
+/// assert_eq!(c.gain(), 64);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CicDecimator<const N: usize> {
+    /// Decimation rate
+    rate: u32,
+    /// Input samples remaining until the next output sample
+    index: u32,
+    /// Integrator state
+    integrators: [i64; N],
+    /// Comb delay state
+    combs: [i64; N],
+}
+
+impl<const N: usize> Default for CicDecimator<N> {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<const N: usize> CicDecimator<N> {
+    /// Create a new zero-state decimator.
+    ///
+    /// # Args
+    /// * `rate`: Decimation rate, `rate >= 1`.
+    pub fn new(rate: u32) -> Self {
+        debug_assert!(rate > 0);
+        Self {
+            rate,
+            index: rate,
+            integrators: [0; N],
+            combs: [0; N],
+        }
+    }
+
+    /// The decimation rate
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Change the decimation rate.
+    ///
+    /// The output phase is restarted. The following few output samples will
+    /// be affected by the transient.
+    pub fn set_rate(&mut self, rate: u32) {
+        debug_assert!(rate > 0);
+        self.rate = rate;
+        self.index = rate;
+    }
+
+    /// DC gain `rate^N`
+    pub fn gain(&self) -> u64 {
+        (self.rate as u64).pow(N as _)
+    }
+
+    /// Logarithm of the DC gain for power-of-two rates, `N*log2(rate)`
+    pub fn gain_log2(&self) -> u32 {
+        debug_assert!(self.rate.is_power_of_two());
+        N as u32 * self.rate.trailing_zeros()
+    }
+
+    /// Number of input samples until the next output sample
+    pub fn tick(&self) -> u32 {
+        self.index
+    }
+
+    /// Ingest a new input sample
+    ///
+    /// # Returns
+    /// The decimated output sample every `rate` input samples,
+    /// `None` otherwise.
+    pub fn update(&mut self, x: i32) -> Option<i64> {
+        let x = self.integrators.iter_mut().fold(x as i64, |x, i| {
+            *i = i.wrapping_add(x);
+            *i
+        });
+        self.index -= 1;
+        if self.index == 0 {
+            self.index = self.rate;
+            Some(self.combs.iter_mut().fold(x, |x, c| {
+                let y = x.wrapping_sub(*c);
+                *c = x;
+                y
+            }))
+        } else {
+            None
+        }
+    }
+}
+
+/// Cascaded integrator comb interpolator
+///
+/// Order `N > 0`, differential delay one, and runtime configurable interpolation
+/// rate `R`. The combs run at the low (input) rate, the integrators at the
+/// high (output) rate with zero-stuffing between them.
+///
+/// The transfer function is the same as that of [`CicDecimator`]. Due to the
+/// zero-stuffing the DC gain is `R^(N - 1)` ([`CicInterpolator::gain()`]).
+///
+/// # Bit growth
+///
+/// The output word grows by `(N - 1)*log2(R)` bits with respect to the
+/// input word. Combs and integrators are wrapping `i64`. The result is
+/// correct as long as `32 + (N - 1)*ceil(log2(R)) <= 64`.
+///
+/// ```
+/// # use idsp::CicInterpolator;
+/// let mut c = CicInterpolator::<2>::new(4);
+/// let y: Vec<_> = (0..12)
+///     .map(|i| c.update((i % 4 == 0).then_some(1)))
+///     .collect();
+/// assert_eq!(y, [1, 2, 3, 4, 4, 4, 4, 4, 4, 4, 4, 4]);
+/// assert_eq!(c.gain(), 4);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CicInterpolator<const N: usize> {
+    /// Interpolation rate
+    rate: u32,
+    /// Output samples remaining until the next input sample
+    index: u32,
+    /// Comb delay state
+    combs: [i64; N],
+    /// Integrator state
+    integrators: [i64; N],
+}
+
+impl<const N: usize> Default for CicInterpolator<N> {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl<const N: usize> CicInterpolator<N> {
+    /// The order `N` must be positive.
+    const ORDER: () = assert!(N > 0, "CicInterpolator order must be positive");
+
+    /// Create a new zero-state interpolator.
+    ///
+    /// # Args
+    /// * `rate`: Interpolation rate, `rate >= 1`.
+    pub fn new(rate: u32) -> Self {
+        let () = Self::ORDER;
+        debug_assert!(rate > 0);
+        Self {
+            rate,
+            index: 0,
+            combs: [0; N],
+            integrators: [0; N],
+        }
+    }
+
+    /// The interpolation rate
+    pub fn rate(&self) -> u32 {
+        self.rate
+    }
+
+    /// Change the interpolation rate.
+    ///
+    /// The next update will expect a new input sample.
+    pub fn set_rate(&mut self, rate: u32) {
+        debug_assert!(rate > 0);
+        self.rate = rate;
+        self.index = 0;
+    }
+
+    /// DC gain `rate^(N - 1)`
+    pub fn gain(&self) -> u64 {
+        (self.rate as u64).pow(N as u32 - 1)
+    }
+
+    /// Logarithm of the DC gain for power-of-two rates, `(N - 1)*log2(rate)`
+    pub fn gain_log2(&self) -> u32 {
+        debug_assert!(self.rate.is_power_of_two());
+        (N as u32 - 1) * self.rate.trailing_zeros()
+    }
+
+    /// Number of output samples until the next input sample is due
+    pub fn tick(&self) -> u32 {
+        self.index
+    }
+
+    /// Compute the next output sample
+    ///
+    /// # Args
+    /// * `x`: New input sample. Must be `Some` on every `rate`-th update
+    ///   (when [`CicInterpolator::tick()`] is zero) and `None` otherwise.
+    ///
+    /// # Returns
+    /// New output sample
+    pub fn update(&mut self, x: Option<i32>) -> i64 {
+        debug_assert_eq!(x.is_some(), self.index == 0);
+        let x = x.map_or(0, |x| {
+            self.combs.iter_mut().fold(x as i64, |x, c| {
+                let y = x.wrapping_sub(*c);
+                *c = x;
+                y
+            })
+        });
+        self.index = if self.index == 0 {
+            self.rate - 1
+        } else {
+            self.index - 1
+        };
+        self.integrators.iter_mut().fold(x, |x, i| {
+            *i = i.wrapping_add(x);
+            *i
+        })
+    }
+}
+
+/// Design CIC droop compensation taps
+///
+/// Computes a symmetric, odd length `2*M - 1` FIR filter running at the low
+/// rate that equalizes the passband droop of a CIC filter of order `order`
+/// and rate `rate` (see [`CicDecimator`] and [`CicInterpolator`]).
+/// The taps are the least squares fit to the inverse CIC magnitude
+/// response (normalized to unity DC gain) from DC to `passband`.
+///
+/// # Args
+/// * `order`: CIC order
+/// * `rate`: CIC rate change
+/// * `passband`: Passband edge in units of the low sample rate, e.g. `0.2`.
+///
+/// # Returns
+/// One-sided taps starting with the center tap.
+/// Apply with [`CicComp`] after quantizing with
+/// [`crate::Coefficient::quantize()`].
+///
+/// ```
+/// # use idsp::cic_compensation;
+/// let h: [f64; 3] = cic_compensation(4, 16, 0.2);
+/// let dc = h[0] + 2.0 * (h[1] + h[2]);
+/// assert!((dc - 1.0).abs() < 1e-3);
+/// assert!(h[0] > 1.0 && h[1] < 0.0);
+/// ```
+pub fn cic_compensation<const M: usize>(order: u32, rate: u32, passband: f64) -> [f64; M] {
+    // Number of frequency grid points
+    const G: usize = 64;
+    let mut a = [[0.0; M]; M];
+    let mut b = [0.0; M];
+    for g in 0..G {
+        let f = passband * g as f64 / (G - 1) as f64;
+        let w = core::f64::consts::PI * f;
+        let cic = if g == 0 {
+            1.0
+        } else {
+            (Float::sin(w) / (rate as f64 * Float::sin(w / rate as f64))).powi(order as _)
+        };
+        let mut phi = [0.0; M];
+        for (k, p) in phi.iter_mut().enumerate() {
+            *p = if k == 0 {
+                1.0
+            } else {
+                2.0 * Float::cos(2.0 * w * k as f64)
+            };
+        }
+        for (aj, (bj, pj)) in a.iter_mut().zip(b.iter_mut().zip(phi.iter())) {
+            *bj += pj / cic;
+            for (ajk, pk) in aj.iter_mut().zip(phi.iter()) {
+                *ajk += pj * pk;
+            }
+        }
+    }
+    solve(a, b)
+}
+
+/// CIC droop compensation filter
+///
+/// A symmetric, odd length `2*M - 1` FIR filter.
+/// Taps are typically designed with [`cic_compensation()`].
+/// The group delay is `M - 1` samples.
+///
+/// ```
+/// # use idsp::{cic_compensation, CicComp, Coefficient, Filter};
+/// let h: [f64; 2] = cic_compensation(3, 8, 0.1);
+/// let h = h.map(i32::quantize);
+/// let mut c = CicComp::default();
+/// for _ in 0..3 {
+///     c.update(1 << 20, &h);
+/// }
+/// assert!((c.get() - (1 << 20)).abs() < 1 << 10);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CicComp<const M: usize> {
+    /// The newest `M` samples, newest first
+    new: [i32; M],
+    /// The `M` samples before that, newest first
+    old: [i32; M],
+    y: i32,
+}
+
+impl<const M: usize> Default for CicComp<M> {
+    fn default() -> Self {
+        Self {
+            new: [0; M],
+            old: [0; M],
+            y: 0,
+        }
+    }
+}
+
+impl<const M: usize> Filter for CicComp<M> {
+    /// One-sided taps, starting with the center tap, in
+    /// [`crate::Coefficient`] scaling for `i32`: `1 << 30` is unity.
+    type Config = [i32; M];
+
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        self.old.copy_within(..M - 1, 1);
+        self.old[0] = self.new[M - 1];
+        self.new.copy_within(..M - 1, 1);
+        self.new[0] = x;
+        let s = k[0] as i64 * self.new[M - 1] as i64
+            + k[1..]
+                .iter()
+                .zip(self.new[..M - 1].iter().rev().zip(self.old.iter()))
+                .map(|(k, (n, o))| *k as i64 * (*n as i64 + *o as i64))
+                .sum::<i64>();
+        self.y = (s >> 30).clamp(i32::MIN as _, i32::MAX as _) as _;
+        self.y
+    }
+
+    fn get(&self) -> i32 {
+        self.y
+    }
+
+    fn set(&mut self, x: i32) {
+        self.new = [x; M];
+        self.old = [x; M];
+        self.y = x;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn impulse() {
+        // Coefficients of (1 + z^-1 + z^-2)^2
+        let h = [1, 2, 3, 2, 1];
+        // Impulse response sampled at all phases
+        for phase in 0..3 {
+            let mut c = CicDecimator::<2>::new(3);
+            let y: Vec<_> = (0..12)
+                .filter_map(|i| c.update((i == phase) as i32))
+                .collect();
+            let want: Vec<_> = (0..4)
+                .map(|j| {
+                    let k = 3 * j + 2 - phase;
+                    *h.get(k).unwrap_or(&0)
+                })
+                .collect();
+            assert_eq!(y, want);
+        }
+    }
+
+    #[test]
+    fn wrap() {
+        let mut c = CicDecimator::<4>::new(1 << 8);
+        assert_eq!(c.gain_log2(), 32);
+        let mut y = 0;
+        for _ in 0..1 << 12 {
+            if let Some(yi) = c.update(i32::MIN) {
+                y = yi;
+            }
+        }
+        assert_eq!(y, (i32::MIN as i64) << 32);
+    }
+
+    #[test]
+    fn interpolate_step() {
+        let mut c = CicInterpolator::<3>::new(1 << 4);
+        assert_eq!(c.gain_log2(), 8);
+        let mut y = 0;
+        for _ in 0..1 << 8 {
+            y = c.update((c.tick() == 0).then_some(-3));
+        }
+        assert_eq!(y, -3 << 8);
+    }
+
+    #[test]
+    fn compensation() {
+        let (order, rate, fp) = (4, 16, 0.25);
+        let h: [f64; 4] = cic_compensation(order, rate, fp);
+        for i in 0..=10 {
+            let w = core::f64::consts::PI * fp * i as f64 / 10.0;
+            let cic = if i == 0 {
+                1.0
+            } else {
+                (w.sin() / (rate as f64 * (w / rate as f64).sin())).powi(order as _)
+            };
+            let comp = h[0]
+                + 2.0
+                    * h[1..]
+                        .iter()
+                        .enumerate()
+                        .map(|(k, h)| h * (2.0 * w * (k + 1) as f64).cos())
+                        .sum::<f64>();
+            let g = 20.0 * (cic * comp).log10();
+            assert!(g.abs() < 0.05, "{i}: {g} dB");
+        }
+    }
+}
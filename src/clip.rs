@@ -0,0 +1,59 @@
+/// Soft clipper
+///
+/// Saturating nonlinearity with a cubic knee as an alternative to hard
+/// clamping (e.g. of [`crate::iir::Biquad`] outputs).
+/// It reduces harmonic generation when the signal approaches the rails.
+///
+/// The transfer function is linear up to `knee` below the limit.
+/// Beyond, a cubic `y = t + d - 4*d**3/(27*knee**2)` with
+/// `d = x - t` and `t = max - knee` bends continuously (value,
+/// first and second derivative) into the linear range and smoothly
+/// (value and first derivative) reaches the limit at `d = 1.5*knee`.
+/// The lower limit is handled symmetrically.
+/// A zero knee is hard clamping.
+///
+/// ```
+/// # use idsp::SoftClip;
+/// let c = SoftClip { min: -1000, max: 1000, knee: 100 };
+/// let y: Vec<_> = [0, 900, 950, 1000, 1050, 2000, -1050]
+///     .iter()
+///     .map(|x| c.apply(*x))
+///     .collect();
+/// assert_eq!(y, [0, 900, 949, 986, 1000, 1000, -1000]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SoftClip {
+    /// Lower limit
+    pub min: i32,
+    /// Upper limit
+    pub max: i32,
+    /// Knee width, `0 <= knee <= (max - min)/2`
+    pub knee: i32,
+}
+
+impl SoftClip {
+    /// Cubic knee for `d = x - t >= 0`, saturating at `1.5*knee`
+    fn bend(&self, d: i64) -> i64 {
+        let w = self.knee as i64;
+        if 2 * d >= 3 * w {
+            w
+        } else {
+            d - ((4 * (d as i128).pow(3)) / (27 * (w as i128).pow(2))) as i64
+        }
+    }
+
+    /// Apply the nonlinearity to a sample.
+    pub fn apply(&self, x: i32) -> i32 {
+        debug_assert!(self.knee >= 0 && self.min as i64 + 2 * self.knee as i64 <= self.max as i64);
+        let (x, w) = (x as i64, self.knee as i64);
+        let hi = self.max as i64 - w;
+        let lo = self.min as i64 + w;
+        (if x > hi {
+            hi + self.bend(x - hi)
+        } else if x < lo {
+            lo - self.bend(lo - x)
+        } else {
+            x
+        }) as i32
+    }
+}
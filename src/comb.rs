@@ -0,0 +1,106 @@
+use crate::{Coefficient, Delay, Filter};
+
+/// Comb filter
+///
+/// `y0 = clamp(x0 + b*x[-D] + a*y[-D], min, max)`
+///
+/// With `b = -1, a = 0` this is the feedforward comb `y0 = x0 - x[-D]` with
+/// zeros at all multiples of `1/D` of the sample rate (including DC).
+/// With additionally `0 < a < 1` the feedback poles at the same
+/// frequencies narrow the notches to a relative bandwidth of
+/// about `(1 - a)/pi`. This rejects periodic disturbances with a known
+/// fundamental (e.g. line frequency and its harmonics).
+/// With `b = 0` this is a feedback comb (resonator).
+///
+/// Coefficients `[b, a]` use the [`Coefficient`] scaling and are decoupled
+/// from the state. Accumulation and saturation are as for
+/// [`crate::fir::Fir`].
+///
+/// ```
+/// # use idsp::Comb;
+/// let mut c = Comb::<_, 2>::default();
+/// let k = [-1.0, 0.0];
+/// let y: Vec<_> = [1.0, 2.0, 1.0, 2.0, 1.0].iter().map(|x| c.update(*x, &k)).collect();
+/// assert_eq!(y, [1.0, 2.0, 0.0, 0.0, 0.0]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Comb<T, const D: usize> {
+    x: Delay<T, D>,
+    y: Delay<T, D>,
+}
+
+impl<T: Coefficient, const D: usize> Default for Comb<T, D> {
+    fn default() -> Self {
+        Self {
+            x: Delay::new(T::ZERO),
+            y: Delay::new(T::ZERO),
+        }
+    }
+}
+
+impl<T: Coefficient, const D: usize> Comb<T, D> {
+    /// Ingest a new input sample and return the new output.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `k`: Coefficients `[b, a]`
+    pub fn update(&mut self, x: T, k: &[T; 2]) -> T {
+        let s = x.as_() * T::ONE.as_()
+            + k[0].as_() * self.x.tap(D - 1).as_()
+            + k[1].as_() * self.y.tap(D - 1).as_();
+        let y = T::ZERO.macc(s, T::MIN, T::MAX, T::ZERO).0;
+        self.x.push(x);
+        self.y.push(y);
+        y
+    }
+
+    /// The current output
+    pub fn get(&self) -> T {
+        self.y.tap(0)
+    }
+
+    /// Set the input and output state.
+    pub fn set(&mut self, x: T, y: T) {
+        self.x.set(x);
+        self.y.set(y);
+    }
+}
+
+impl<const D: usize> Filter for Comb<i32, D> {
+    /// Coefficients `[b, a]` where `1 << 30` is unity
+    type Config = [i32; 2];
+
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        Comb::update(self, x, k)
+    }
+
+    fn get(&self) -> i32 {
+        Comb::get(self)
+    }
+
+    /// Set the steady state for an input of `x` with a feedforward notch
+    /// (`b = -1`): zero output.
+    fn set(&mut self, x: i32) {
+        Comb::set(self, x, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn notch() {
+        // Reject a 50 Hz disturbance sampled at 1 kHz
+        let k = [-1.0, 0.99].map(i32::quantize);
+        let mut c = Comb::<i32, 20>::default();
+        let mut y = 0;
+        for i in 0..20_000 {
+            let p = core::f64::consts::TAU * i as f64 / 20.0;
+            let x = 1000 + (1e6 * (p.sin() + 0.5 * (3.0 * p).cos())) as i32;
+            y = Filter::update(&mut c, x, &k);
+        }
+        // Truncation bias is amplified by the feedback
+        assert!(y.abs() < 200, "{y}");
+    }
+}
@@ -1,6 +1,6 @@
 pub use num_complex::Complex;
 
-use super::{atan2, cossin};
+use super::{atan2, cossin, isqrt};
 
 /// Complex extension trait offering DSP (fast, good accuracy) functionality.
 pub trait ComplexExt<T, U> {
@@ -137,3 +137,17 @@ impl MulScaled<i16> for Complex<i32> {
         }
     }
 }
+
+/// Complex magnitude (integer hypot)
+///
+/// The normalization is that of the components, rounded down.
+///
+/// ```
+/// use idsp::{magnitude, Complex};
+/// assert_eq!(magnitude(Complex::new(3 << 20, -4 << 20)), 5 << 20);
+/// assert_eq!(magnitude(Complex::new(i32::MIN, i32::MIN)), 0xb504_f333);
+/// ```
+pub fn magnitude(iq: Complex<i32>) -> u32 {
+    let (re, im) = (iq.re.unsigned_abs() as u64, iq.im.unsigned_abs() as u64);
+    isqrt(re * re + im * im) as u32
+}
@@ -0,0 +1,75 @@
+use num_traits::Float;
+
+use super::{Pid, PidState};
+
+/// Cascade (outer/inner) loop controller
+///
+/// The outer controller output is the setpoint of the inner controller.
+/// The outer controller output limits bound the inner setpoint.
+///
+/// While the inner controller output is saturated, the outer integrator
+/// is held if it would drive the inner loop further into saturation
+/// (conditional integration) and the stored outer output is made consistent
+/// with the held integrator. This prevents windup of the outer loop
+/// while the inner loop cannot follow. Positive inner gains are assumed.
+///
+/// The configuration is decoupled from the state ([`CascadeState`]).
+///
+/// ```
+/// # use idsp::control::*;
+/// let c = Cascade {
+///     outer: Pid { ki: 0.0625, ..Default::default() },
+///     inner: Pid { kp: 1.0, max: 1.0, ..Default::default() },
+/// };
+/// let mut s = CascadeState::default();
+/// for _ in 0..10 {
+///     c.update(&mut s, 8.0, 0.0, 0.0);
+/// }
+/// // Outer integrator held once the inner loop saturates
+/// assert_eq!(s.outer.i, 0.5);
+/// assert_eq!(s.outer.y, 0.5);
+/// assert_eq!(s.inner.y, 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Cascade<T> {
+    /// Outer controller, output is the inner setpoint
+    pub outer: Pid<T>,
+    /// Inner controller
+    pub inner: Pid<T>,
+}
+
+/// [`Cascade`] state
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct CascadeState<T> {
+    /// Outer controller state
+    pub outer: PidState<T>,
+    /// Inner controller state
+    pub inner: PidState<T>,
+}
+
+impl<T: Float> Cascade<T> {
+    /// Update the controllers.
+    ///
+    /// # Args
+    /// * `state`: Controller state
+    /// * `setpoint`: Outer loop setpoint
+    /// * `outer`: Outer loop measurement
+    /// * `inner`: Inner loop measurement
+    ///
+    /// # Returns
+    /// The inner controller output.
+    pub fn update(&self, state: &mut CascadeState<T>, setpoint: T, outer: T, inner: T) -> T {
+        let i = state.outer.i;
+        let r = self.outer.update(&mut state.outer, setpoint - outer);
+        let y = self.inner.update(&mut state.inner, r - inner);
+        let di = state.outer.i - i;
+        if (y >= self.inner.max && di > T::zero()) || (y <= self.inner.min && di < T::zero()) {
+            let o = &mut state.outer;
+            o.i = i;
+            o.y = (self.outer.kp * o.x + o.i + o.d)
+                .max(self.outer.min)
+                .min(self.outer.max);
+        }
+        y
+    }
+}
@@ -0,0 +1,137 @@
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+
+use crate::Delay;
+
+/// A feedforward term of [`Feedforward`]
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct FeedforwardTerm<T> {
+    /// Gain
+    pub gain: T,
+    /// Delay in samples, less than the delay line length
+    pub delay: usize,
+    /// Add this term to the output
+    pub enable: bool,
+}
+
+impl<T: Float> Default for FeedforwardTerm<T> {
+    fn default() -> Self {
+        Self {
+            gain: T::one(),
+            delay: 0,
+            enable: true,
+        }
+    }
+}
+
+/// Feedback and feedforward combiner
+///
+/// Sums a feedback controller output and `K` scaled and delayed feedforward
+/// terms and clamps the sum to the output limits.
+/// The amount by which the sum exceeds the limits is available as
+/// [`FeedforwardState::excess()`], e.g. for back-calculation
+/// anti-windup of the feedback controller.
+///
+/// The configuration is decoupled from the state ([`FeedforwardState`]).
+///
+/// ```
+/// # use idsp::control::*;
+/// let ff = Feedforward {
+///     terms: [
+///         FeedforwardTerm { gain: 0.5, ..Default::default() },
+///         FeedforwardTerm { gain: 2.0, delay: 1, ..Default::default() },
+///     ],
+///     max: 10.0,
+///     ..Default::default()
+/// };
+/// let mut s = FeedforwardState::<_, 2, 4>::default();
+/// assert_eq!(ff.update(&mut s, 1.0, [2.0, 3.0]), 2.0);
+/// assert_eq!(ff.update(&mut s, 1.0, [2.0, 3.0]), 8.0);
+/// assert_eq!(ff.update(&mut s, 4.0, [2.0, 3.0]), 10.0);
+/// assert_eq!(s.excess(), 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Feedforward<T, const K: usize> {
+    /// Feedforward terms
+    pub terms: [FeedforwardTerm<T>; K],
+    /// Lower output limit
+    pub min: T,
+    /// Upper output limit
+    pub max: T,
+}
+
+impl<T: Float, const K: usize> Default for Feedforward<T, K> {
+    fn default() -> Self {
+        Self {
+            terms: [FeedforwardTerm::default(); K],
+            min: T::neg_infinity(),
+            max: T::infinity(),
+        }
+    }
+}
+
+/// [`Feedforward`] state
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FeedforwardState<T, const K: usize, const N: usize> {
+    x: [Delay<T, N>; K],
+    excess: T,
+}
+
+impl<T: Float + Default, const K: usize, const N: usize> Default for FeedforwardState<T, K, N> {
+    fn default() -> Self {
+        Self {
+            x: [Delay::default(); K],
+            excess: T::zero(),
+        }
+    }
+}
+
+impl<T: Float, const K: usize, const N: usize> FeedforwardState<T, K, N> {
+    /// Amount by which the last unclamped sum exceeded the output limits
+    ///
+    /// Positive above the upper limit, negative below the lower limit,
+    /// zero otherwise.
+    pub fn excess(&self) -> T {
+        self.excess
+    }
+
+    /// Fill the feedforward delay lines with the given values.
+    pub fn set(&mut self, x: [T; K]) {
+        for (d, x) in self.x.iter_mut().zip(x) {
+            d.set(x);
+        }
+        self.excess = T::zero();
+    }
+}
+
+impl<T: Float, const K: usize> Feedforward<T, K> {
+    /// Update the combiner.
+    ///
+    /// The feedforward inputs are always pushed into the delay lines,
+    /// also for disabled terms.
+    ///
+    /// # Args
+    /// * `state`: Combiner state
+    /// * `y`: Feedback controller output
+    /// * `x`: Feedforward inputs
+    ///
+    /// # Returns
+    /// The combined output, clamped to `[min, max]`.
+    pub fn update<const N: usize>(
+        &self,
+        state: &mut FeedforwardState<T, K, N>,
+        y: T,
+        x: [T; K],
+    ) -> T {
+        let mut u = y;
+        for ((t, d), x) in self.terms.iter().zip(state.x.iter_mut()).zip(x) {
+            d.push(x);
+            if t.enable {
+                u = u + t.gain * d.tap(t.delay);
+            }
+        }
+        let y = u.max(self.min).min(self.max);
+        state.excess = u - y;
+        y
+    }
+}
@@ -0,0 +1,14 @@
+//! Controllers and control loop structures
+
+mod cascade;
+pub use cascade::*;
+mod feedforward;
+pub use feedforward::*;
+mod pid;
+pub use pid::*;
+mod relay;
+pub use relay::*;
+mod schedule;
+pub use schedule::*;
+mod smith;
+pub use smith::*;
@@ -0,0 +1,165 @@
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+
+/// Integrator anti-windup mode
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum AntiWindup<T> {
+    /// No anti-windup: the integrator is unbounded
+    None,
+    /// Conditional integration: the integrator is held while the output is
+    /// saturated and the error drives it further into saturation
+    #[default]
+    Clamp,
+    /// Back-calculation: the difference between the saturated and the
+    /// unsaturated output is fed back into the integrator with the given gain
+    BackCalculation(T),
+}
+
+/// PID controller
+///
+/// Parallel form PID controller with explicit proportional, integral, and
+/// derivative gains, a first order lowpass on the derivative term,
+/// output limits, and selectable integrator anti-windup.
+/// See [`crate::iir::Pid`] and [`crate::iir::Biquad`] for the
+/// transfer function based alternative.
+///
+/// Gains are in units of the sample period: `ki` is the integral gain per
+/// sample (`Ki*T`) and `kd` is the derivative gain in samples (`Kd/T`).
+///
+/// The configuration is decoupled from the state ([`PidState`]).
+/// The input is the error signal (setpoint minus measurement).
+///
+/// ```
+/// # use idsp::control::*;
+/// let pid = Pid {
+///     kp: 2.0,
+///     ki: 0.5,
+///     max: 3.0,
+///     ..Default::default()
+/// };
+/// let mut s = PidState::default();
+/// let y: Vec<_> = (0..4).map(|_| pid.update(&mut s, 1.0)).collect();
+/// assert_eq!(y, [2.5, 3.0, 3.0, 3.0]);
+/// // Conditional integration: no windup, immediate recovery
+/// assert_eq!(pid.update(&mut s, -1.0), -1.5);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Pid<T> {
+    /// Proportional gain
+    pub kp: T,
+    /// Integral gain per sample
+    pub ki: T,
+    /// Derivative gain in samples
+    pub kd: T,
+    /// Derivative lowpass weight, `0 < alpha <= 1`, `1` for no filtering.
+    ///
+    /// The derivative lowpass time constant is `1/alpha - 1` samples.
+    pub alpha: T,
+    /// Lower output limit
+    pub min: T,
+    /// Upper output limit
+    pub max: T,
+    /// Anti-windup mode
+    pub anti_windup: AntiWindup<T>,
+}
+
+impl<T: Float> Default for Pid<T> {
+    fn default() -> Self {
+        Self {
+            kp: T::zero(),
+            ki: T::zero(),
+            kd: T::zero(),
+            alpha: T::one(),
+            min: T::neg_infinity(),
+            max: T::infinity(),
+            anti_windup: AntiWindup::default(),
+        }
+    }
+}
+
+/// [`Pid`] controller state
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct PidState<T> {
+    /// Integrator
+    pub i: T,
+    /// Filtered derivative term
+    pub d: T,
+    /// Previous error
+    pub x: T,
+    /// Previous output
+    pub y: T,
+}
+
+impl<T: Float> Pid<T> {
+    /// Update the controller with a new error sample.
+    ///
+    /// # Args
+    /// * `state`: Controller state
+    /// * `x`: Error sample
+    ///
+    /// # Returns
+    /// The new output, clamped to `[min, max]`.
+    pub fn update(&self, state: &mut PidState<T>, x: T) -> T {
+        state.d = state.d + self.alpha * (self.kd * (x - state.x) - state.d);
+        state.x = x;
+        let i = state.i + self.ki * x;
+        let u = self.kp * x + i + state.d;
+        let y = u.max(self.min).min(self.max);
+        state.i = match self.anti_windup {
+            AntiWindup::None => i,
+            AntiWindup::Clamp => {
+                let di = self.ki * x;
+                if (u > self.max && di > T::zero()) || (u < self.min && di < T::zero()) {
+                    state.i
+                } else {
+                    i
+                }
+            }
+            AntiWindup::BackCalculation(kt) => i + kt * (y - u),
+        };
+        state.y = y;
+        y
+    }
+
+    /// Set the integrator such that the current error yields the given
+    /// output (bumpless transfer).
+    ///
+    /// # Args
+    /// * `state`: Controller state
+    /// * `y`: Desired output
+    pub fn set_output(&self, state: &mut PidState<T>, y: T) {
+        state.i = y - self.kp * state.x - state.d;
+        state.y = y;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn windup() {
+        let mut pid = Pid {
+            kp: 1.0,
+            ki: 0.1,
+            min: -1.0,
+            max: 1.0,
+            anti_windup: AntiWindup::None,
+            ..Default::default()
+        };
+        let recover = |pid: &Pid<f64>| {
+            let mut s = PidState::default();
+            for _ in 0..100 {
+                pid.update(&mut s, 2.0);
+            }
+            (0..100)
+                .position(|_| pid.update(&mut s, -0.5) < 1.0)
+                .unwrap_or(100)
+        };
+        assert!(recover(&pid) > 50);
+        pid.anti_windup = AntiWindup::Clamp;
+        assert_eq!(recover(&pid), 0);
+        pid.anti_windup = AntiWindup::BackCalculation(1.0);
+        assert_eq!(recover(&pid), 0);
+    }
+}
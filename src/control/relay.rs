@@ -0,0 +1,223 @@
+use num_traits::{Float, FloatConst};
+use serde::{Deserialize, Serialize};
+
+use crate::iir::{Action, Pid};
+
+/// Ultimate gain and period of a loop
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Ultimate<T> {
+    /// Ultimate gain: the proportional gain at the stability limit
+    pub gain: T,
+    /// Ultimate period: the oscillation period at the stability limit,
+    /// in samples
+    pub period: T,
+}
+
+impl<T: Float> Ultimate<T> {
+    /// Ziegler-Nichols PI gains as an [`crate::iir::Pid`] builder
+    ///
+    /// `Kp = 0.45*Ku`, `Ti = Tu/1.2`.
+    /// The gains are in units of the sample period
+    /// (the builder period is `1`).
+    pub fn iir_pi(&self) -> Pid<T> {
+        let kp = T::from(0.45).unwrap() * self.gain;
+        let mut pid = Pid::default();
+        pid.gain(Action::Kp, kp)
+            .gain(Action::Ki, kp * T::from(1.2).unwrap() / self.period);
+        pid
+    }
+
+    /// Ziegler-Nichols PID gains as an [`crate::iir::Pid`] builder
+    ///
+    /// `Kp = 0.6*Ku`, `Ti = Tu/2`, `Td = Tu/8`.
+    /// See also [`Ultimate::iir_pi()`].
+    pub fn iir_pid(&self) -> Pid<T> {
+        let kp = T::from(0.6).unwrap() * self.gain;
+        let mut pid = Pid::default();
+        pid.gain(Action::Kp, kp)
+            .gain(Action::Ki, kp * T::from(2.0).unwrap() / self.period)
+            .gain(Action::Kd, kp * self.period / T::from(8.0).unwrap());
+        pid
+    }
+}
+
+/// Relay feedback autotuner
+///
+/// Åström-Hägglund relay autotuning: the plant is excited with a relay
+/// (bang-bang controller with hysteresis) of the error signal.
+/// This leads to a limit cycle close to the ultimate period.
+/// The oscillation period is measured between rising relay transitions
+/// and the amplitude from the error extrema in each period.
+/// The ultimate gain is estimated using the describing function of the relay
+/// with hysteresis: `Ku = 4*d/(pi*sqrt(a**2 - h**2))`.
+///
+/// The first period is discarded as a transient.
+/// The configuration is decoupled from the state ([`RelayTunerState`]).
+///
+/// ```
+/// # use idsp::{control::*, iir::*, Delay};
+/// let relay = RelayTuner { amplitude: 1.0, hysteresis: 0.0, cycles: 4 };
+/// let mut s = RelayTunerState::default();
+/// // Pure dead time plant with gain 2
+/// let mut plant = Delay::<f64, 4>::default();
+/// let mut y = 0.0;
+/// let mut u = None;
+/// while u.is_none() {
+///     let x = relay.update(&mut s, -y);
+///     y = 2.0 * plant.push(x);
+///     u = s.ultimate(&relay);
+/// }
+/// let u = u.unwrap();
+/// assert_eq!(u.period, 10.0);
+/// assert!((u.gain - 2.0 / core::f64::consts::PI).abs() < 1e-9);
+/// let b: Biquad<f64> = u.iir_pi().build().unwrap().into();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct RelayTuner<T> {
+    /// Relay output amplitude `d`
+    pub amplitude: T,
+    /// Relay input hysteresis `h` in error units
+    pub hysteresis: T,
+    /// Number of periods to average
+    pub cycles: u32,
+}
+
+/// [`RelayTuner`] state
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct RelayTunerState<T> {
+    high: bool,
+    /// Samples since the last rising transition
+    n: u32,
+    /// Number of periods completed, including the first
+    cycles: u32,
+    /// Sum of the periods
+    period: u64,
+    /// Sum of the peak-peak amplitudes
+    amplitude: T,
+    /// Error extrema in the current period
+    min: T,
+    max: T,
+}
+
+impl<T: Float> RelayTuner<T> {
+    /// Update the relay.
+    ///
+    /// # Args
+    /// * `state`: Autotuner state
+    /// * `x`: Error sample (setpoint minus measurement)
+    ///
+    /// # Returns
+    /// The relay output (plant input), `0` once done.
+    pub fn update(&self, state: &mut RelayTunerState<T>, x: T) -> T {
+        if state.cycles > self.cycles {
+            return T::zero();
+        }
+        state.n += 1;
+        state.min = state.min.min(x);
+        state.max = state.max.max(x);
+        if state.high {
+            if x < -self.hysteresis {
+                state.high = false;
+            }
+        } else if x > self.hysteresis {
+            state.high = true;
+            if state.cycles > 0 {
+                state.period += state.n as u64;
+                state.amplitude = state.amplitude + state.max - state.min;
+            }
+            state.cycles += 1;
+            state.n = 0;
+            state.min = x;
+            state.max = x;
+        }
+        if state.high {
+            self.amplitude
+        } else {
+            -self.amplitude
+        }
+    }
+}
+
+impl<T: Float + FloatConst> RelayTunerState<T> {
+    /// Whether the measurement is complete
+    pub fn done(&self, k: &RelayTuner<T>) -> bool {
+        self.cycles > k.cycles
+    }
+
+    /// The estimated ultimate gain and period
+    ///
+    /// `None` while the measurement is incomplete, if no periods are
+    /// averaged (`cycles == 0`), or if the oscillation amplitude does
+    /// not exceed the hysteresis.
+    pub fn ultimate(&self, k: &RelayTuner<T>) -> Option<Ultimate<T>> {
+        if !self.done(k) || k.cycles == 0 {
+            return None;
+        }
+        let n = T::from(k.cycles).unwrap();
+        let a = self.amplitude / (n + n);
+        if a <= k.hysteresis {
+            return None;
+        }
+        Some(Ultimate {
+            gain: T::from(4.0).unwrap() * k.amplitude
+                / (T::PI() * (a * a - k.hysteresis * k.hysteresis).sqrt()),
+            period: T::from(self.period).unwrap() / n,
+        })
+    }
+
+    /// Restart the measurement.
+    pub fn reset(&mut self) {
+        *self = Self {
+            high: self.high,
+            n: 0,
+            cycles: 0,
+            period: 0,
+            amplitude: T::zero(),
+            min: T::zero(),
+            max: T::zero(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn no_cycles() {
+        let relay = RelayTuner {
+            amplitude: 1.0,
+            hysteresis: 0.0,
+            cycles: 0,
+        };
+        let mut s = RelayTunerState::default();
+        for x in [-1.0, 1.0, -1.0] {
+            relay.update(&mut s, x);
+        }
+        assert!(s.done(&relay));
+        assert_eq!(s.ultimate(&relay), None);
+    }
+
+    #[test]
+    fn hysteresis() {
+        let mut relay = RelayTuner {
+            amplitude: 1.0,
+            hysteresis: 0.5,
+            cycles: 2,
+        };
+        let s = RelayTunerState {
+            cycles: 3,
+            period: 20,
+            amplitude: 2.0,
+            ..Default::default()
+        };
+        // a = 0.5
+        assert_eq!(s.ultimate(&relay), None);
+        relay.hysteresis = 0.6;
+        assert_eq!(s.ultimate(&relay), None);
+        relay.hysteresis = 0.4;
+        let u = s.ultimate(&relay).unwrap();
+        assert_eq!(u.period, 10.0);
+        assert!(u.gain.is_finite());
+    }
+}
@@ -0,0 +1,148 @@
+use num_traits::Float;
+
+use super::{Pid, PidState};
+
+/// Breakpoint table gain scheduler for [`Pid`]
+///
+/// Selects or interpolates controller gains based on a scheduling variable
+/// (e.g. the setpoint or the measured operating point).
+///
+/// The breakpoints must be sorted ascending by their scheduling variable
+/// value. The active segment `i` covers `[breakpoints[i].0, breakpoints[i + 1].0)`.
+/// It only changes once the scheduling variable leaves the segment by more
+/// than the hysteresis.
+///
+/// Without interpolation the gains of the lower segment breakpoint
+/// are used. With interpolation `kp`, `ki`, `kd`, and `alpha` are
+/// interpolated linearly between the segment breakpoints.
+/// Output limits and anti-windup are always taken from the lower breakpoint.
+///
+/// Retuning is bumpless: when the gains change, the integrator is adjusted such
+/// that the new gains reproduce the last output for the last error
+/// (see [`Pid::set_output()`]).
+///
+/// ```
+/// # use idsp::control::*;
+/// let g = GainSchedule {
+///     breakpoints: [
+///         (0.0, Pid { kp: 1.0, ..Default::default() }),
+///         (10.0, Pid { kp: 2.0, ..Default::default() }),
+///     ],
+///     hysteresis: 1.0,
+///     interpolate: false,
+/// };
+/// let mut idx = 0;
+/// let mut pid = g.get(&mut idx, 0.0);
+/// let mut s = PidState::default();
+/// assert!(!g.update(&mut idx, &mut pid, &mut s, 10.5));
+/// assert_eq!((idx, pid.kp), (0, 1.0));
+/// assert_eq!(pid.update(&mut s, 1.0), 1.0);
+/// assert!(g.update(&mut idx, &mut pid, &mut s, 11.5));
+/// assert_eq!((idx, pid.kp), (1, 2.0));
+/// // Bumpless
+/// assert_eq!(pid.update(&mut s, 1.0), 1.0);
+/// assert!(!g.update(&mut idx, &mut pid, &mut s, 9.5));
+/// assert_eq!((idx, pid.kp), (1, 2.0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct GainSchedule<T, const N: usize> {
+    /// Scheduling variable values and gains
+    pub breakpoints: [(T, Pid<T>); N],
+    /// Segment switching hysteresis in scheduling variable units
+    pub hysteresis: T,
+    /// Interpolate gains within a segment
+    pub interpolate: bool,
+}
+
+impl<T: Float, const N: usize> GainSchedule<T, N> {
+    /// Determine the scheduled gains.
+    ///
+    /// # Args
+    /// * `index`: Active segment index
+    /// * `v`: Scheduling variable
+    ///
+    /// # Returns
+    /// The controller with the scheduled gains.
+    pub fn get(&self, index: &mut usize, v: T) -> Pid<T> {
+        debug_assert!(N > 0);
+        let bp = &self.breakpoints;
+        while *index + 1 < N && v >= bp[*index + 1].0 + self.hysteresis {
+            *index += 1;
+        }
+        while *index > 0 && v < bp[*index].0 - self.hysteresis {
+            *index -= 1;
+        }
+        let (v0, mut pid) = bp[*index];
+        if self.interpolate && *index + 1 < N {
+            let (v1, p1) = bp[*index + 1];
+            let t = ((v - v0) / (v1 - v0)).max(T::zero()).min(T::one());
+            let lerp = |a: T, b: T| a + t * (b - a);
+            pid.kp = lerp(pid.kp, p1.kp);
+            pid.ki = lerp(pid.ki, p1.ki);
+            pid.kd = lerp(pid.kd, p1.kd);
+            pid.alpha = lerp(pid.alpha, p1.alpha);
+        }
+        pid
+    }
+
+    /// Determine the scheduled gains and retune bumplessly on change.
+    ///
+    /// The controller and its state are only modified if the scheduled
+    /// controller differs from the active one (e.g. after a segment change
+    /// between distinct breakpoints or when interpolating). Otherwise the
+    /// state and the anti-windup behavior are left untouched.
+    ///
+    /// # Args
+    /// * `index`: Active segment index
+    /// * `pid`: Active controller, replaced by the scheduled controller
+    /// * `state`: Controller state to adjust
+    /// * `v`: Scheduling variable
+    ///
+    /// # Returns
+    /// Whether the controller was retuned.
+    pub fn update(
+        &self,
+        index: &mut usize,
+        pid: &mut Pid<T>,
+        state: &mut PidState<T>,
+        v: T,
+    ) -> bool {
+        let new = self.get(index, v);
+        let retune = new != *pid;
+        if retune {
+            new.set_output(state, state.y);
+            *pid = new;
+        }
+        retune
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn constant_saturated() {
+        let p = Pid {
+            kp: 1.0,
+            ki: 0.1,
+            min: -1.0,
+            max: 1.0,
+            ..Default::default()
+        };
+        let g = GainSchedule {
+            breakpoints: [(0.0, p), (1.0, p)],
+            hysteresis: 0.1,
+            interpolate: true,
+        };
+        let mut idx = 0;
+        let mut pid = g.get(&mut idx, 0.0);
+        let (mut s, mut s0) = (PidState::default(), PidState::default());
+        for k in 0..200 {
+            let x = if k < 100 { 2.0 } else { -0.5 };
+            g.update(&mut idx, &mut pid, &mut s, (k % 30) as f64 / 10.0);
+            assert_eq!(pid.update(&mut s, x), p.update(&mut s0, x));
+            assert_eq!(s, s0);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use num_traits::Float;
+
+use super::{Pid, PidState};
+use crate::Delay;
+
+/// Smith predictor
+///
+/// Controller for plants with dominant dead time.
+/// An internal plant model without dead time (first order lag
+/// `ym0 = ym1 + alpha*(gain*u - ym1)`) and a delay line of its output
+/// predict the measurement. The controller acts on the error
+/// between the setpoint and the measurement corrected by the difference
+/// between the undelayed and delayed model outputs.
+/// With an accurate model, the loop response is that of the loop
+/// without dead time, delayed.
+///
+/// The configuration is decoupled from the state ([`SmithState`]).
+/// The delay line length `N` must exceed the model dead time.
+///
+/// ```
+/// # use idsp::{control::*, Delay};
+/// let smith = Smith {
+///     pid: Pid { kp: 1.0, ki: 0.2, ..Default::default() },
+///     gain: 2.0,
+///     alpha: 0.1,
+///     delay: 20,
+/// };
+/// let mut s = SmithState::<_, 32>::default();
+/// // Plant matching the model
+/// let (mut y, mut dead) = (0.0, Delay::<f64, 20>::default());
+/// let mut peak = 0.0f64;
+/// for _ in 0..500 {
+///     let u = smith.update(&mut s, 1.0, dead.push(y));
+///     y += 0.1 * (2.0 * u - y);
+///     peak = peak.max(dead.tap(0));
+/// }
+/// assert!((dead.tap(0) - 1.0).abs() < 1e-6);
+/// assert!(peak < 1.1);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Smith<T> {
+    /// Controller
+    pub pid: Pid<T>,
+    /// Plant model gain
+    pub gain: T,
+    /// Plant model lowpass weight, `0 < alpha <= 1`
+    pub alpha: T,
+    /// Plant model dead time in samples, `delay < N`
+    pub delay: usize,
+}
+
+/// [`Smith`] state
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SmithState<T, const N: usize> {
+    /// Controller state
+    pub pid: PidState<T>,
+    /// Undelayed model output history
+    model: Delay<T, N>,
+}
+
+impl<T: Float + Default, const N: usize> Default for SmithState<T, N> {
+    fn default() -> Self {
+        Self {
+            pid: PidState::default(),
+            model: Delay::default(),
+        }
+    }
+}
+
+impl<T: Float, const N: usize> SmithState<T, N> {
+    /// The current undelayed model output
+    pub fn prediction(&self) -> T {
+        self.model.tap(0)
+    }
+}
+
+impl<T: Float> Smith<T> {
+    /// Update the predictor and the controller.
+    ///
+    /// # Args
+    /// * `state`: Predictor state
+    /// * `setpoint`: Setpoint
+    /// * `y`: Plant measurement
+    ///
+    /// # Returns
+    /// The controller output (plant input).
+    pub fn update<const N: usize>(&self, state: &mut SmithState<T, N>, setpoint: T, y: T) -> T {
+        let ym = state.model.tap(0);
+        let x = setpoint - y - (ym - state.model.tap(self.delay));
+        let u = self.pid.update(&mut state.pid, x);
+        state.model.push(ym + self.alpha * (self.gain * u - ym));
+        u
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn equivalence() {
+        const D: usize = 7;
+        let smith = Smith {
+            pid: Pid {
+                kp: 0.3,
+                ki: 0.05,
+                kd: 0.5,
+                alpha: 0.5,
+                max: 0.6,
+                ..Default::default()
+            },
+            gain: 1.5,
+            alpha: 0.2,
+            delay: D,
+        };
+        // Loop without dead time
+        let mut s0 = PidState::default();
+        let mut y0 = 0.0;
+        let mut free = [0.0; 200];
+        for f in free.iter_mut() {
+            *f = y0;
+            let u = smith.pid.update(&mut s0, 1.0 - y0);
+            y0 += smith.alpha * (smith.gain * u - y0);
+        }
+        // Smith predictor around the plant with dead time
+        let mut s = SmithState::<_, 8>::default();
+        let mut dead = Delay::<f64, D>::default();
+        let mut y = 0.0;
+        for i in 0..free.len() {
+            let yd = dead.push(y);
+            if i >= D {
+                let f = free[i - D];
+                assert!((yd - f).abs() < 1e-12, "{i}: {yd} != {f}");
+            }
+            let u = smith.update(&mut s, 1.0, yd);
+            y += smith.alpha * (smith.gain * u - y);
+        }
+    }
+}
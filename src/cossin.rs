@@ -89,7 +89,7 @@ mod tests {
         const PHASE_DEPTH: usize = 20;
 
         for phase in 0..(1 << PHASE_DEPTH) {
-            let phase = (phase << (32 - PHASE_DEPTH)) as i32;
+            let phase = phase << (32 - PHASE_DEPTH);
             let have = cossin(phase);
             // file.write(&have.0.to_le_bytes()).unwrap();
             // file.write(&have.1.to_le_bytes()).unwrap();
@@ -0,0 +1,140 @@
+/// Debouncer / glitch filter for boolean streams
+///
+/// The output only toggles once the input has differed from it for a
+/// given number of consecutive samples. Shorter glitches are suppressed
+/// and the output edge is delayed by that number of samples.
+///
+/// ```
+/// # use idsp::Debounce;
+/// let mut d = Debounce::default();
+/// let y: Vec<_> = [true, false, true, true, true, false, true, false, false, false]
+///     .iter()
+///     .map(|x| d.update(*x, 3))
+///     .collect();
+/// assert_eq!(
+///     y,
+///     [false, false, false, false, true, true, true, true, true, false]
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Debounce {
+    state: bool,
+    count: u32,
+}
+
+impl Debounce {
+    /// Create a new debouncer in the given state.
+    pub fn new(state: bool) -> Self {
+        Self { state, count: 0 }
+    }
+
+    /// Ingest a new sample
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `n`: Number of consecutive samples the input needs to differ
+    ///   from the output for the output to toggle. `n <= 1` disables
+    ///   debouncing.
+    ///
+    /// # Returns
+    /// The new output state.
+    pub fn update(&mut self, x: bool, n: u32) -> bool {
+        if x == self.state {
+            self.count = 0;
+        } else {
+            self.count += 1;
+            if self.count >= n {
+                self.state = x;
+                self.count = 0;
+            }
+        }
+        self.state
+    }
+
+    /// The current output state
+    pub fn get(&self) -> bool {
+        self.state
+    }
+
+    /// Number of consecutive samples the input has differed from the output
+    pub fn pending(&self) -> u32 {
+        self.count
+    }
+
+    /// Set the output state and clear any pending transition.
+    pub fn set(&mut self, state: bool) {
+        self.state = state;
+        self.count = 0;
+    }
+}
+
+/// Event rate and duty cycle estimator for boolean streams
+///
+/// Counts rising edges and estimates the event rate and the duty
+/// cycle (fraction of samples that are `true`) with exponential
+/// averaging over `1 << k` samples.
+///
+/// Rate and duty cycle are unsigned fixed point with 32 fractional bits.
+/// The rate is in units of events per sample, matching the frequency
+/// convention (full turn per sample is `1 << 32`).
+///
+/// ```
+/// # use idsp::EventRate;
+/// let mut e = EventRate::default();
+/// for i in 0..1 << 12 {
+///     // Period 8, duty cycle 3/8
+///     e.update(i % 8 < 3, 8);
+/// }
+/// assert_eq!(e.count(), 1 << 9);
+/// assert!((e.rate() as i64 - (1 << 29)).abs() < 1 << 25);
+/// assert!((e.duty() as i64 - (3 << 29)).abs() < 1 << 26);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventRate {
+    state: bool,
+    rate: i64,
+    duty: i64,
+    count: u32,
+}
+
+impl EventRate {
+    /// Ingest a new sample
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `k`: Averaging time constant `1 << k` samples, `k < 32`
+    ///
+    /// # Returns
+    /// Whether a rising edge occurred.
+    pub fn update(&mut self, x: bool, k: u32) -> bool {
+        let edge = x && !self.state;
+        self.state = x;
+        self.count = self.count.wrapping_add(edge as _);
+        self.rate += (((edge as i64) << 32) - self.rate) >> k;
+        self.duty += (((x as i64) << 32) - self.duty) >> k;
+        edge
+    }
+
+    /// Rising edge rate in units of events per sample
+    pub fn rate(&self) -> u64 {
+        self.rate as _
+    }
+
+    /// Duty cycle
+    pub fn duty(&self) -> u64 {
+        self.duty as _
+    }
+
+    /// Number of rising edges since the last reset
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Clear the estimates and the edge counter.
+    pub fn reset(&mut self) {
+        *self = Self {
+            state: self.state,
+            ..Default::default()
+        };
+    }
+}
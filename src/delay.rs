@@ -0,0 +1,73 @@
+/// Delay line
+///
+/// A ring buffer of the last `N` samples.
+///
+/// ```
+/// # use idsp::Delay;
+/// let mut d = Delay::<i32, 3>::default();
+/// assert_eq!(d.push(1), 0);
+/// d.push(2);
+/// d.push(3);
+/// assert_eq!(d.push(4), 1);
+/// assert_eq!(d.tap(0), 4);
+/// assert_eq!(d.tap(2), 2);
+/// assert!(d.iter().eq([4, 3, 2].iter()));
+/// assert_eq!(d.as_slices(), (&[2, 3][..], &[4][..]));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Delay<T, const N: usize> {
+    x: [T; N],
+    idx: usize,
+}
+
+impl<T: Copy + Default, const N: usize> Default for Delay<T, N> {
+    fn default() -> Self {
+        Self {
+            x: [T::default(); N],
+            idx: 0,
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> Delay<T, N> {
+    /// Create a new delay line filled with the given value.
+    pub fn new(x: T) -> Self {
+        Self { x: [x; N], idx: 0 }
+    }
+
+    /// Push a new sample into the delay line.
+    ///
+    /// # Returns
+    /// The oldest sample, delayed by `N` samples, which is dropped.
+    pub fn push(&mut self, x: T) -> T {
+        let old = core::mem::replace(&mut self.x[self.idx], x);
+        self.idx = if self.idx + 1 == N { 0 } else { self.idx + 1 };
+        old
+    }
+
+    /// Access a previous sample
+    ///
+    /// # Args
+    /// * `k`: Delay in samples: `0` is the newest, `N - 1` the oldest sample.
+    pub fn tap(&self, k: usize) -> T {
+        debug_assert!(k < N);
+        self.x[(self.idx + N - 1 - k) % N]
+    }
+
+    /// Iterate over the samples, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let (old, new) = self.as_slices();
+        new.iter().rev().chain(old.iter().rev())
+    }
+
+    /// The entire buffer, oldest sample first, as two contiguous slices
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (new, old) = self.x.split_at(self.idx);
+        (old, new)
+    }
+
+    /// Fill the delay line with a constant value.
+    pub fn set(&mut self, x: T) {
+        self.x = [x; N];
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{magnitude, Complex, ComplexExt, Ewma, Filter, Unwrapper};
+
+/// Quadrature frequency discriminator
+///
+/// Computes the instantaneous frequency from consecutive I/Q samples as the
+/// wrapping difference of their phases (`atan2()`).
+/// The frequency is in units of the sample rate: a full turn per sample is
+/// `1 << 32`. It is unambiguous for frequencies below half the sample rate.
+/// The phase is unwrapped and accumulated without loss, e.g. for frequency
+/// noise measurements of beat notes.
+///
+/// ```
+/// # use idsp::{Complex, ComplexExt, FmDiscriminator};
+/// let mut d = FmDiscriminator::default();
+/// let f = 0x1234_5678;
+/// let mut y = 0;
+/// for i in 0..100i32 {
+///     y = d.update(Complex::from_angle(i.wrapping_mul(f)));
+/// }
+/// assert!((y - f).abs() < 1 << 14);
+/// assert!((d.phase() - 99 * f as i64).abs() < 1 << 14);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct FmDiscriminator {
+    phase: Unwrapper<i64>,
+}
+
+impl FmDiscriminator {
+    /// Ingest a new I/Q sample and return the instantaneous frequency.
+    pub fn update(&mut self, iq: Complex<i32>) -> i32 {
+        self.phase.update(iq.arg())
+    }
+
+    /// The unwrapped phase, a full turn is `1 << 32`
+    pub fn phase(&self) -> i64 {
+        self.phase.y()
+    }
+}
+
+/// [`AmDemod`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AmDemodConfig<C> {
+    /// DC blocker weight (see [`Ewma`]), `0` disables DC blocking
+    pub dc: u32,
+    /// Lowpass filter configuration
+    pub lowpass: C,
+}
+
+/// AM demodulator
+///
+/// Computes the magnitude of the I/Q input, removes its DC component
+/// (the carrier) with an exponential moving average and lowpass filters
+/// the result.
+///
+/// ```
+/// # use idsp::{AmDemod, AmDemodConfig, Complex, Lowpass};
+/// let mut d = AmDemod::<Lowpass<1>>::default();
+/// let k = AmDemodConfig { dc: 1 << 28, lowpass: [1 << 28] };
+/// let mut y = 0;
+/// for _ in 0..1000 {
+///     y = d.update(Complex::new(3 << 20, 4 << 20), &k);
+/// }
+/// assert!((d.carrier() - (5 << 20)).abs() < 1 << 4);
+/// assert!(y.abs() < 1 << 4);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct AmDemod<F> {
+    dc: Ewma,
+    lowpass: F,
+}
+
+impl<F: Filter> AmDemod<F> {
+    /// Ingest a new I/Q sample and return the demodulated amplitude.
+    pub fn update(&mut self, iq: Complex<i32>, k: &AmDemodConfig<F::Config>) -> i32 {
+        let m = magnitude(iq).min(i32::MAX as _) as i32;
+        let dc = if k.dc == 0 {
+            0
+        } else {
+            self.dc.update(m, &k.dc)
+        };
+        self.lowpass.update(m.saturating_sub(dc), &k.lowpass)
+    }
+
+    /// The current carrier (DC) magnitude estimate
+    pub fn carrier(&self) -> i32 {
+        self.dc.get()
+    }
+}
+
+/// FM demodulator
+///
+/// A [`FmDiscriminator`] followed by a lowpass filter.
+///
+/// ```
+/// # use idsp::{Complex, ComplexExt, FmDemod, Lowpass};
+/// let mut d = FmDemod::<Lowpass<2>>::default();
+/// let f = 1 << 24;
+/// let mut y = 0;
+/// for i in 0..10_000i32 {
+///     y = d.update(Complex::from_angle(i.wrapping_mul(f)), &[1 << 12, -0x5a_8279]);
+/// }
+/// assert!((y - f).abs() < 1 << 14);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct FmDemod<F> {
+    discriminator: FmDiscriminator,
+    lowpass: F,
+}
+
+impl<F: Filter> FmDemod<F> {
+    /// Ingest a new I/Q sample and return the demodulated frequency.
+    pub fn update(&mut self, iq: Complex<i32>, k: &F::Config) -> i32 {
+        self.lowpass.update(self.discriminator.update(iq), k)
+    }
+
+    /// The discriminator
+    pub fn discriminator(&self) -> &FmDiscriminator {
+        &self.discriminator
+    }
+}
@@ -1,3 +1,5 @@
+use crate::Tpdf;
+
 /// Delta-sigma modulator
 ///
 /// * MASH-(1)^K architecture
@@ -53,3 +55,178 @@ impl<const K: usize> Dsm<K> {
         })
     }
 }
+
+/// Error feedback delta-sigma modulator
+///
+/// Quantizes a high resolution input to a coarse output code with
+/// `N`-th order noise shaping. The noise transfer function is `(1 - z^-1)^N`.
+/// The state is bounded for all inputs. For `N > 1` and in particular with
+/// few output bits the internal value clips for inputs close to full scale,
+/// degrading the noise shaping. Use `N = 1` or `N = 2`.
+///
+/// The output code `y` represents the value `y << shift`. Its range is
+/// `i32::MIN >> shift..=i32::MAX >> shift`, e.g. `-1..=0` for a one bit output
+/// with `shift = 31`.
+/// Given constant input `x0`, the average output is `x0 >> shift` including
+/// the fractional part.
+///
+/// ```
+/// # use idsp::DeltaSigma;
+/// let mut d = DeltaSigma::<2>::default();
+/// let x = -0x1234_5678;
+/// let n = 1 << 16;
+/// let y: Vec<_> = (0..n).map(|_| d.update(x, 28)).collect();
+/// assert!(y.iter().all(|y| (-3..=0).contains(y)));
+/// let m = y.iter().map(|y| *y as i64).sum::<i64>();
+/// assert!(((m << 28) / n - x as i64).abs() < 1 << 14);
+///
+/// // One bit output
+/// let y = (0..n).map(|_| d.update(-1 << 29, 31) as i64).sum::<i64>();
+/// assert!((y - (-n / 4)).abs() < 4);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeltaSigma<const N: usize> {
+    /// Quantization error history, newest first
+    e: [i64; N],
+}
+
+impl<const N: usize> Default for DeltaSigma<N> {
+    fn default() -> Self {
+        Self { e: [0; N] }
+    }
+}
+
+impl<const N: usize> DeltaSigma<N> {
+    /// Ingest input sample, emit new output code.
+    ///
+    /// # Arguments
+    /// * `x`: New input sample
+    /// * `shift`: Number of input bits to quantize away, `shift < 32`
+    ///
+    /// # Returns
+    /// New output code
+    pub fn update(&mut self, x: i32, shift: u32) -> i32 {
+        // v = x - ((1 - z^-1)^N - 1) e
+        let mut c = 1;
+        let mut v = x as i64;
+        for (k, e) in self.e.iter().enumerate() {
+            c = -c * (N - k) as i64 / (k + 1) as i64;
+            v -= c * e;
+        }
+        let v = v.clamp(i32::MIN as _, i32::MAX as _);
+        let y = v >> shift;
+        self.e.copy_within(..N.saturating_sub(1), 1);
+        if let Some(e) = self.e.first_mut() {
+            *e = v - (y << shift);
+        }
+        y as _
+    }
+
+    /// Reset the modulator state.
+    pub fn reset(&mut self) {
+        self.e = [0; N];
+    }
+}
+
+/// Noise shaped PWM duty cycle generator
+///
+/// Converts a control word into PWM compare values (duty cycles in timer
+/// counts) with first order error feedback from one PWM period to the next.
+/// The average duty cycle has the full resolution of the control word
+/// even though each period is quantized to timer counts.
+///
+/// The control word is offset binary: `i32::MIN` maps to zero duty cycle
+/// and `i32::MAX` to almost full duty cycle (`period - 1` counts).
+///
+/// ```
+/// # use idsp::Pwm;
+/// let mut p = Pwm::default();
+/// let y: Vec<_> = (0..8).map(|_| p.update(-1 << 29, 10)).collect();
+/// assert_eq!(y, [3, 4, 4, 4, 3, 4, 4, 4]);
+/// assert_eq!(y.iter().sum::<u32>(), 30); // 3/8 * 10 * 8
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pwm {
+    /// Duty cycle residue in units of `1 << 32` counts
+    e: u32,
+}
+
+impl Pwm {
+    /// Compute the compare value for the next PWM period.
+    ///
+    /// # Arguments
+    /// * `x`: Control word
+    /// * `period`: PWM period in timer counts
+    ///
+    /// # Returns
+    /// Duty cycle in timer counts, `0..period`
+    pub fn update(&mut self, x: i32, period: u32) -> u32 {
+        let v = ((x as u32 ^ 1 << 31) as u64 * period as u64) + self.e as u64;
+        self.e = v as u32;
+        (v >> 32) as _
+    }
+
+    /// Reset the error feedback state.
+    pub fn reset(&mut self) {
+        self.e = 0;
+    }
+}
+
+/// Noise shaping quantizer
+///
+/// Reduces the word size of a signal (e.g. 32 bit controller output to
+/// 16 bit DAC codes) using a [`DeltaSigma`] modulator of order `N`
+/// (`1 <= N <= 3`) and optional [`Tpdf`] dither.
+///
+/// ```
+/// # use idsp::Quantizer;
+/// let mut q = Quantizer::<3>::new(Some(7));
+/// let x = 0x1234_5678;
+/// let n = 1 << 16;
+/// let y: Vec<_> = (0..n).map(|_| q.update(x, 16)).collect();
+/// assert!(y.iter().all(|y| (y - (x >> 16)).abs() <= 8));
+/// let m = y.iter().map(|y| *y as i64).sum::<i64>();
+/// assert!(((m << 16) / n - x as i64).abs() < 1 << 8);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Quantizer<const N: usize> {
+    dsm: DeltaSigma<N>,
+    dither: Option<Tpdf>,
+}
+
+impl<const N: usize> Default for Quantizer<N> {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl<const N: usize> Quantizer<N> {
+    /// The order `N` must be 1, 2, or 3.
+    const ORDER: () = assert!(N >= 1 && N <= 3, "Quantizer order must be 1, 2, or 3");
+
+    /// Create a new quantizer.
+    ///
+    /// # Arguments
+    /// * `dither`: Dither random number generator seed, `None` for no dither
+    pub fn new(dither: Option<u64>) -> Self {
+        let () = Self::ORDER;
+        Self {
+            dsm: DeltaSigma::default(),
+            dither: dither.map(Tpdf::new),
+        }
+    }
+
+    /// Quantize an input sample.
+    ///
+    /// # Arguments
+    /// * `x`: New input sample
+    /// * `bits`: Output word size, `0 < bits <= 32`
+    ///
+    /// # Returns
+    /// Output code in the signed `bits` wide range
+    pub fn update(&mut self, x: i32, bits: u32) -> i32 {
+        let shift = 32 - bits;
+        let d = self.dither.as_mut().map(|d| d.update(shift)).unwrap_or(0);
+        self.dsm.update(x.saturating_add(d), shift)
+    }
+}
@@ -0,0 +1,114 @@
+use core::ops::{Neg, Shr};
+
+use num_traits::{AsPrimitive, Float};
+
+use crate::{Coefficient, Complex};
+
+/// In-place fixed point radix-2 FFT
+///
+/// Computes the forward DFT `X[k] = sum(x[n]*exp(-2 pi i n k/N))`
+/// of a power-of-two length `N` buffer in place (decimation in time).
+///
+/// Block floating point scaling: before each of the `log2(N)` stages
+/// all values are shifted right by one bit if any component could
+/// overflow in that stage. The returned block exponent is the number
+/// of shifts: the DFT is `x << exponent`. Inputs are typically full scale,
+/// then the exponent is close to `log2(N)` (unitary scaling is
+/// `log2(N)/2`).
+///
+/// The inverse DFT can be computed as the complex conjugate of the FFT
+/// of the complex conjugate.
+///
+/// # Args
+/// * `x`: Data, `x.len()` a power of two
+///
+/// # Returns
+/// Block exponent
+///
+/// ```
+/// # use idsp::{fft, Complex};
+/// let mut x = [Complex::new(1i16 << 12, 0); 8];
+/// let e = fft(&mut x);
+/// assert_eq!(e, 2);
+/// assert_eq!(x[0], Complex::new(1 << 13, 0)); // (8 << 12) >> 2
+/// assert!(x[1..].iter().all(|x| *x == Complex::new(0, 0)));
+/// ```
+pub fn fft<T>(x: &mut [Complex<T>]) -> u32
+where
+    T: Coefficient + PartialOrd + Neg<Output = T> + Shr<u32, Output = T> + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    let n = x.len();
+    debug_assert!(n.is_power_of_two());
+    if n < 2 {
+        return 0;
+    }
+    // Bit reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            x.swap(i, j);
+        }
+    }
+    // Butterflies grow components by up to 1 + sqrt(2)
+    let lim = T::MAX >> 2;
+    let mut exponent = 0;
+    let mut m = 1;
+    while m < n {
+        if x.iter()
+            .any(|x| x.re > lim || x.re < -lim || x.im > lim || x.im < -lim)
+        {
+            for x in x.iter_mut() {
+                x.re = x.re >> 1;
+                x.im = x.im >> 1;
+            }
+            exponent += 1;
+        }
+        for j in 0..m {
+            let p = -core::f64::consts::PI * j as f64 / m as f64;
+            let (s, c) = (Float::sin(p), Float::cos(p));
+            let w = Complex::new(T::quantize(c), T::quantize(s));
+            for i in (j..n).step_by(2 * m) {
+                let (a, b) = (x[i], x[i + m]);
+                let t = Complex::new(
+                    w.re.mul_scaled(b.re) - w.im.mul_scaled(b.im),
+                    w.re.mul_scaled(b.im) + w.im.mul_scaled(b.re),
+                );
+                x[i] = Complex::new(a.re + t.re, a.im + t.im);
+                x[i + m] = Complex::new(a.re - t.re, a.im - t.im);
+            }
+        }
+        m *= 2;
+    }
+    exponent
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn dft() {
+        let mut rng = StdRng::seed_from_u64(42);
+        const N: usize = 64;
+        let x: [Complex<i32>; N] =
+            core::array::from_fn(|_| Complex::new(rng.gen::<i32>() >> 1, rng.gen::<i32>() >> 1));
+        let mut y = x;
+        let e = fft(&mut y);
+        assert!(e >= 5);
+        for (k, y) in y.iter().enumerate() {
+            let mut re = 0.0;
+            let mut im = 0.0;
+            for (n, x) in x.iter().enumerate() {
+                let p = -core::f64::consts::TAU * (n * k) as f64 / N as f64;
+                re += x.re as f64 * p.cos() - x.im as f64 * p.sin();
+                im += x.re as f64 * p.sin() + x.im as f64 * p.cos();
+            }
+            let s = (1u64 << e) as f64;
+            assert!((y.re as f64 - re / s).abs() < 8.0, "{k} {y} {re}");
+            assert!((y.im as f64 - im / s).abs() < 8.0, "{k} {y} {im}");
+        }
+    }
+}
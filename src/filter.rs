@@ -19,6 +19,74 @@ pub trait Filter {
     /// Update the filter so that it outputs the provided value.
     /// This does not completely define the state of the filter.
     fn set(&mut self, x: i32);
+
+    /// Update the filter with a sample held for `n` sample periods.
+    ///
+    /// This accounts for `n - 1` missed samples (e.g. after a DMA underrun)
+    /// preceding `x`, preserving the filter time constants.
+    /// The missed samples are assumed to be equal to `x`.
+    ///
+    /// The default implementation calls [`Filter::update()`] `n` times.
+    ///
+    /// ```
+    /// # use idsp::{Filter, Lowpass};
+    /// let k = [1 << 28];
+    /// let mut f = Lowpass::<1>::default();
+    /// let mut g = f;
+    /// f.update(1 << 20, &k);
+    /// f.update(1 << 20, &k);
+    /// let y = f.update(1 << 20, &k);
+    /// assert_eq!(g.update_n(1 << 20, 3, &k), y);
+    /// ```
+    ///
+    /// # Args
+    /// * `x`: Input data.
+    /// * `n`: Number of sample periods, `n > 0`.
+    /// * `k`: Filter configuration.
+    ///
+    /// # Return
+    /// Filtered output y.
+    fn update_n(&mut self, x: i32, n: u32, k: &Self::Config) -> i32 {
+        let mut y = self.get();
+        for _ in 0..n {
+            y = self.update(x, k);
+        }
+        y
+    }
+
+    /// Process a block of samples in place.
+    ///
+    /// The default implementation calls [`Filter::update()`] for each sample.
+    /// Implementations may override this with a more efficient version.
+    ///
+    /// # Args
+    /// * `xy`: Input data on entry, filtered output on exit.
+    /// * `k`: Filter configuration.
+    ///
+    /// ```
+    /// # use idsp::{Filter, MovingAverage};
+    /// let mut f = MovingAverage::<i32, 2>::default();
+    /// let mut xy = [2, 4, 6];
+    /// Filter::process(&mut f, &mut xy, &());
+    /// assert_eq!(xy, [1, 3, 5]);
+    /// ```
+    fn process(&mut self, xy: &mut [i32], k: &Self::Config) {
+        for xy in xy.iter_mut() {
+            *xy = self.update(*xy, k);
+        }
+    }
+
+    /// Process a block of samples out of place.
+    ///
+    /// # Args
+    /// * `x`: Input data.
+    /// * `y`: Filtered output, same length as `x`.
+    /// * `k`: Filter configuration.
+    fn process_into(&mut self, x: &[i32], y: &mut [i32], k: &Self::Config) {
+        debug_assert_eq!(x.len(), y.len());
+        y.copy_from_slice(x);
+        self.process(y, k);
+    }
 }
 
 /// Nyquist zero
@@ -50,6 +118,9 @@ impl<const N: usize, T: Filter> Filter for Repeat<N, T> {
     fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
         self.0.iter_mut().fold(x, |x, stage| stage.update(x, k))
     }
+    fn process(&mut self, xy: &mut [i32], k: &Self::Config) {
+        self.0.iter_mut().for_each(|stage| stage.process(xy, k));
+    }
     fn get(&self) -> i32 {
         self.0[N - 1].get()
     }
@@ -71,6 +142,10 @@ impl<T: Filter, U: Filter> Filter for Cascade<T, U> {
     fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
         self.1.update(self.0.update(x, &k.0), &k.1)
     }
+    fn process(&mut self, xy: &mut [i32], k: &Self::Config) {
+        self.0.process(xy, &k.0);
+        self.1.process(xy, &k.1);
+    }
     fn get(&self) -> i32 {
         self.1.get()
     }
@@ -78,3 +153,94 @@ impl<T: Filter, U: Filter> Filter for Cascade<T, U> {
         self.1.set(x)
     }
 }
+
+/// Dyn-compatible single input single output i32 filter
+///
+/// Unlike [`Filter`], the configuration is bound into the implementor
+/// (see [`Configured`]) such that stages of different types and
+/// configurations can be combined as trait objects at runtime (see [`Chain`]).
+pub trait DynFilter {
+    /// Update the filter with a new sample and return the output.
+    fn update(&mut self, x: i32) -> i32;
+    /// Return the current filter output
+    fn get(&self) -> i32;
+    /// Update the filter so that it outputs the provided value.
+    fn set(&mut self, x: i32);
+    /// Process a block of samples in place.
+    fn process(&mut self, xy: &mut [i32]) {
+        for xy in xy.iter_mut() {
+            *xy = self.update(*xy);
+        }
+    }
+}
+
+/// A [`Filter`] together with its configuration
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Configured<F: Filter> {
+    /// The filter state
+    pub filter: F,
+    /// The filter configuration
+    pub config: F::Config,
+}
+
+impl<F: Filter> Configured<F> {
+    /// Bind a filter and its configuration.
+    pub fn new(filter: F, config: F::Config) -> Self {
+        Self { filter, config }
+    }
+}
+
+impl<F: Filter> DynFilter for Configured<F> {
+    fn update(&mut self, x: i32) -> i32 {
+        self.filter.update(x, &self.config)
+    }
+    fn get(&self) -> i32 {
+        self.filter.get()
+    }
+    fn set(&mut self, x: i32) {
+        self.filter.set(x)
+    }
+    fn process(&mut self, xy: &mut [i32]) {
+        self.filter.process(xy, &self.config)
+    }
+}
+
+/// Runtime cascade of dynamic filters
+///
+/// The order and presence of the stages can be selected at runtime.
+/// An empty chain is the identity.
+///
+/// ```
+/// # use idsp::{Chain, Configured, DynFilter, MovingAverage, SlewLimiter};
+/// let mut ma = Configured::new(MovingAverage::<i32, 2>::default(), ());
+/// let mut slew = Configured::new(SlewLimiter::default(), [3, 3]);
+/// let mut stages: [&mut dyn DynFilter; 2] = [&mut slew, &mut ma];
+/// let mut c = Chain::new(&mut stages[..]);
+/// let mut xy = [8, 8, 8, 8];
+/// c.process(&mut xy);
+/// assert_eq!(xy, [1, 4, 7, 8]);
+/// assert_eq!(c.get(), 8);
+/// ```
+pub struct Chain<'a, 'b>(&'a mut [&'b mut dyn DynFilter]);
+
+impl<'a, 'b> Chain<'a, 'b> {
+    /// Create a new chain from the given stages, first stage first.
+    pub fn new(stages: &'a mut [&'b mut dyn DynFilter]) -> Self {
+        Self(stages)
+    }
+}
+
+impl DynFilter for Chain<'_, '_> {
+    fn update(&mut self, x: i32) -> i32 {
+        self.0.iter_mut().fold(x, |x, stage| stage.update(x))
+    }
+    fn get(&self) -> i32 {
+        self.0.last().map(|stage| stage.get()).unwrap_or_default()
+    }
+    fn set(&mut self, x: i32) {
+        self.0.iter_mut().for_each(|stage| stage.set(x));
+    }
+    fn process(&mut self, xy: &mut [i32]) {
+        self.0.iter_mut().for_each(|stage| stage.process(xy));
+    }
+}
@@ -0,0 +1,875 @@
+//! FIR filters
+
+use num_traits::{Float, Zero};
+
+use crate::{solve, Coefficient, Complex, Filter};
+
+/// FIR filter
+///
+/// `y0 = clamp(sum(h[k]*x[-k] for k in 0..N), min, max)`
+///
+/// The state is a circular buffer of the last `N` input samples.
+/// The taps (`h`, newest sample first) are decoupled from the state and
+/// can be shared between multiple channels.
+///
+/// Taps use the [`Coefficient`] scaling. Products are accumulated in the wide
+/// accumulator type [`Coefficient::ACCU`] and the result saturates to the
+/// sample type range. For fixed point types there are two guard bits in the
+/// accumulator. The taps must be scaled such that the accumulator does not
+/// overflow for the expected input.
+///
+/// ```
+/// # use idsp::fir::Fir;
+/// let h = [0.5, 0.25, 0.25];
+/// let mut f = Fir::default();
+/// let mut xy = [4.0, 8.0, 0.0, 4.0];
+/// f.process(&mut xy, &h);
+/// assert_eq!(xy, [2.0, 5.0, 3.0, 4.0]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fir<T, const N: usize> {
+    x: [T; N],
+    idx: usize,
+    y: T,
+}
+
+impl<T: Coefficient, const N: usize> Default for Fir<T, N> {
+    fn default() -> Self {
+        Self {
+            x: [T::ZERO; N],
+            idx: 0,
+            y: T::ZERO,
+        }
+    }
+}
+
+impl<T: Coefficient, const N: usize> Fir<T, N> {
+    /// Ingest a new input sample and return the new output.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `h`: Filter taps, newest sample first
+    pub fn update(&mut self, x: T, h: &[T; N]) -> T {
+        self.x[self.idx] = x;
+        let (new, old) = self.x.split_at(self.idx + 1);
+        let s = new
+            .iter()
+            .rev()
+            .chain(old.iter().rev())
+            .zip(h.iter())
+            .fold(T::ZERO.as_(), |s, (x, h)| s + x.as_() * h.as_());
+        self.idx = if self.idx + 1 == N { 0 } else { self.idx + 1 };
+        self.y = T::ZERO.macc(s, T::MIN, T::MAX, T::ZERO).0;
+        self.y
+    }
+
+    /// The last output
+    pub fn get(&self) -> T {
+        self.y
+    }
+
+    /// Process a block of samples in place.
+    ///
+    /// # Args
+    /// * `xy`: Input samples on entry, output samples on exit
+    /// * `h`: Filter taps, newest sample first
+    pub fn process(&mut self, xy: &mut [T], h: &[T; N]) {
+        for xy in xy.iter_mut() {
+            *xy = self.update(*xy, h);
+        }
+    }
+
+    /// Access a previous input sample
+    ///
+    /// # Args
+    /// * `k`: Delay in samples: `0` is the newest, `N - 1` the oldest sample.
+    pub fn x(&self, k: usize) -> T {
+        debug_assert!(k < N);
+        self.x[(self.idx + N - 1 - k) % N]
+    }
+
+    /// Fill the state with a constant input value.
+    pub fn set(&mut self, x: T) {
+        self.x = [x; N];
+    }
+}
+
+impl<const N: usize> Filter for Fir<i32, N> {
+    /// Filter taps, newest sample first, in [`Coefficient`] scaling:
+    /// `1 << 30` is unity.
+    type Config = [i32; N];
+
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        Fir::update(self, x, k)
+    }
+
+    fn get(&self) -> i32 {
+        Fir::get(self)
+    }
+
+    /// Set the steady state for an input of `x` with unity DC gain taps.
+    fn set(&mut self, x: i32) {
+        Fir::set(self, x);
+        self.y = x;
+    }
+}
+
+/// Symmetric (linear phase) FIR filter
+///
+/// A FIR filter of length `N` with symmetric taps `h[k] = h[N - 1 - k]`.
+/// Only the `M = (N + 1)/2` unique taps are used: one multiplication per
+/// pair of samples.
+///
+/// The unique taps are the outer taps first: `h[0], ..., h[M - 1]`.
+/// For odd `N`, `h[M - 1]` is the center tap.
+/// The group delay is `(N - 1)/2` samples.
+///
+/// Scaling, accumulation and saturation are as for [`Fir`].
+///
+/// ```
+/// # use idsp::fir::{Fir, Symmetric};
+/// let h = [0.25, 0.5]; // [0.25, 0.5, 0.25]
+/// let mut f = Symmetric::<_, 2, 3>::default();
+/// let mut g = Fir::default();
+/// for x in [1.0, -3.0, 2.0, 5.0] {
+///     assert_eq!(f.update(x, &h), g.update(x, &[0.25, 0.5, 0.25]));
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Symmetric<T, const M: usize, const N: usize> {
+    x: [T; N],
+    idx: usize,
+}
+
+impl<T: Coefficient, const M: usize, const N: usize> Default for Symmetric<T, M, N> {
+    fn default() -> Self {
+        debug_assert_eq!(M, N.div_ceil(2));
+        Self {
+            x: [T::ZERO; N],
+            idx: 0,
+        }
+    }
+}
+
+impl<T: Coefficient, const M: usize, const N: usize> Symmetric<T, M, N> {
+    /// Ingest a new input sample and return the new output.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `h`: Unique filter taps, outer taps first
+    pub fn update(&mut self, x: T, h: &[T; M]) -> T {
+        self.x[self.idx] = x;
+        let (new, old) = self.x.split_at(self.idx + 1);
+        let x = new.iter().rev().chain(old.iter().rev());
+        let mut s = x
+            .clone()
+            .zip(x.rev())
+            .zip(h.iter())
+            .take(N / 2)
+            .fold(T::ZERO.as_(), |s, ((x0, x1), h)| {
+                s + (x0.as_() + x1.as_()) * h.as_()
+            });
+        if N & 1 == 1 {
+            s = s + self.x[(self.idx + N - N / 2) % N].as_() * h[M - 1].as_();
+        }
+        self.idx = if self.idx + 1 == N { 0 } else { self.idx + 1 };
+        T::ZERO.macc(s, T::MIN, T::MAX, T::ZERO).0
+    }
+
+    /// Process a block of samples in place.
+    ///
+    /// See [`Symmetric::update()`].
+    pub fn process(&mut self, xy: &mut [T], h: &[T; M]) {
+        for xy in xy.iter_mut() {
+            *xy = self.update(*xy, h);
+        }
+    }
+
+    /// Fill the state with a constant input value.
+    pub fn set(&mut self, x: T) {
+        self.x = [x; N];
+    }
+}
+
+/// Decompose a FIR prototype into polyphase branches
+///
+/// Branch `p` receives the taps `h[p + R*j]` for `j in 0..K`.
+/// Missing taps (`h.len() < R*K`) are zero.
+///
+/// ```
+/// # use idsp::fir::polyphase;
+/// let h = polyphase::<_, 2, 3>(&[1, 2, 3, 4, 5]);
+/// assert_eq!(h, [[1, 3, 5], [2, 4, 0]]);
+/// ```
+pub fn polyphase<T: Copy + Zero, const R: usize, const K: usize>(h: &[T]) -> [[T; K]; R] {
+    debug_assert!(h.len() <= R * K);
+    let mut p = [[T::zero(); K]; R];
+    for (i, h) in h.iter().enumerate() {
+        p[i % R][i / R] = *h;
+    }
+    p
+}
+
+/// Polyphase decimating FIR filter
+///
+/// Decimates by `R` using a FIR filter of length `R*K`.
+/// The `R` polyphase branches have `K` taps each (see [`polyphase()`]).
+/// Only the output samples are computed: `K` multiplications per input
+/// sample instead of `R*K`.
+///
+/// Scaling, accumulation and saturation are as for [`Fir`].
+///
+/// ```
+/// # use idsp::fir::{polyphase, Decimator};
+/// let h = polyphase::<_, 2, 2>(&[0.25, 0.25, 0.25, 0.25]);
+/// let mut d = Decimator::default();
+/// let x = [1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+/// let mut y = [0.0; 3];
+/// assert_eq!(d.process(&x, &mut y, &h), &[0.5, 1.5, 2.5]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Decimator<T, const R: usize, const K: usize> {
+    /// Input samples by phase class
+    x: [[T; K]; R],
+    /// Branch ring buffer index
+    idx: usize,
+    /// Input phase
+    phase: usize,
+}
+
+impl<T: Coefficient, const R: usize, const K: usize> Default for Decimator<T, R, K> {
+    fn default() -> Self {
+        Self {
+            x: [[T::ZERO; K]; R],
+            idx: 0,
+            phase: 0,
+        }
+    }
+}
+
+impl<T: Coefficient, const R: usize, const K: usize> Decimator<T, R, K> {
+    /// Ingest a new input sample
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `h`: Polyphase taps, see [`polyphase()`].
+    ///
+    /// # Returns
+    /// The new output sample for every `R`-th input sample, `None` otherwise.
+    pub fn update(&mut self, x: T, h: &[[T; K]; R]) -> Option<T> {
+        self.x[self.phase][self.idx] = x;
+        self.phase += 1;
+        if self.phase < R {
+            return None;
+        }
+        self.phase = 0;
+        let mut s = T::ZERO.as_();
+        for (xp, hp) in self.x.iter().rev().zip(h.iter()) {
+            let (new, old) = xp.split_at(self.idx + 1);
+            s = new
+                .iter()
+                .rev()
+                .chain(old.iter().rev())
+                .zip(hp.iter())
+                .fold(s, |s, (x, h)| s + x.as_() * h.as_());
+        }
+        self.idx = if self.idx + 1 == K { 0 } else { self.idx + 1 };
+        Some(T::ZERO.macc(s, T::MIN, T::MAX, T::ZERO).0)
+    }
+
+    /// Process a block of samples
+    ///
+    /// # Args
+    /// * `x`: Input samples
+    /// * `y`: Output sample buffer. Must be large enough to hold all output
+    ///   samples.
+    /// * `h`: Polyphase taps
+    ///
+    /// # Returns
+    /// The output samples written into `y`.
+    pub fn process<'a>(&mut self, x: &[T], y: &'a mut [T], h: &[[T; K]; R]) -> &'a mut [T] {
+        let mut n = 0;
+        for x in x.iter() {
+            if let Some(yi) = self.update(*x, h) {
+                y[n] = yi;
+                n += 1;
+            }
+        }
+        &mut y[..n]
+    }
+
+    /// Number of input samples ingested for the next output sample
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+}
+
+/// Polyphase interpolating FIR filter
+///
+/// Interpolates by `R` using a FIR filter of length `R*K`:
+/// zero-stuffing followed by FIR filtering, folded into the `R` polyphase
+/// branches with `K` taps each (see [`polyphase()`]).
+/// Only `K` multiplications per output sample are computed.
+///
+/// Due to the zero-stuffing, the prototype taps need a DC gain of `R` to
+/// obtain unity gain.
+///
+/// Scaling, accumulation and saturation are as for [`Fir`].
+///
+/// ```
+/// # use idsp::fir::{polyphase, Interpolator};
+/// // Linear interpolation
+/// let h = polyphase::<_, 2, 2>(&[0.5, 1.0, 0.5]);
+/// let mut i = Interpolator::default();
+/// let mut y = [0.0; 6];
+/// i.process(&[2.0, 4.0, 4.0], &mut y, &h);
+/// assert_eq!(y, [1.0, 2.0, 3.0, 4.0, 4.0, 4.0]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Interpolator<T, const R: usize, const K: usize> {
+    x: [T; K],
+    idx: usize,
+}
+
+impl<T: Coefficient, const R: usize, const K: usize> Default for Interpolator<T, R, K> {
+    fn default() -> Self {
+        Self {
+            x: [T::ZERO; K],
+            idx: 0,
+        }
+    }
+}
+
+impl<T: Coefficient, const R: usize, const K: usize> Interpolator<T, R, K> {
+    /// Ingest a new input sample and compute the next `R` output samples.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `h`: Polyphase taps, see [`polyphase()`].
+    pub fn update(&mut self, x: T, h: &[[T; K]; R]) -> [T; R] {
+        self.x[self.idx] = x;
+        let (new, old) = self.x.split_at(self.idx + 1);
+        let y = h.map(|hp| {
+            let s = new
+                .iter()
+                .rev()
+                .chain(old.iter().rev())
+                .zip(hp.iter())
+                .fold(T::ZERO.as_(), |s, (x, h)| s + x.as_() * h.as_());
+            T::ZERO.macc(s, T::MIN, T::MAX, T::ZERO).0
+        });
+        self.idx = if self.idx + 1 == K { 0 } else { self.idx + 1 };
+        y
+    }
+
+    /// Process a block of samples
+    ///
+    /// # Args
+    /// * `x`: Input samples
+    /// * `y`: Output samples. Must have length `R*x.len()`.
+    /// * `h`: Polyphase taps
+    pub fn process(&mut self, x: &[T], y: &mut [T], h: &[[T; K]; R]) {
+        debug_assert_eq!(y.len(), x.len() * R);
+        for (x, y) in x.iter().zip(y.chunks_exact_mut(R)) {
+            y.copy_from_slice(&self.update(*x, h));
+        }
+    }
+}
+
+/// Cubic Farrow fractional delay interpolator
+///
+/// Third order Lagrange interpolation in Farrow structure: the polynomial
+/// coefficients are computed from the last four input samples and the
+/// polynomial is evaluated at a fractional delay that can change with every
+/// output sample. This allows timing recovery and sample clock offset
+/// correction between asynchronous domains.
+///
+/// The interpolation is between the second and third newest samples: the total
+/// delay is `1 + mu` samples. Cubic polynomials are reproduced exactly
+/// up to quantization errors of a few LSB.
+///
+/// Samples are `i32` and the output saturates.
+///
+/// ```
+/// # use idsp::fir::Farrow;
+/// let mut f = Farrow::default();
+/// for x in [0, 1 << 20, 2 << 20, 3 << 20] {
+///     f.push(x);
+/// }
+/// assert_eq!(f.interpolate(0), 2 << 20);
+/// assert_eq!(f.interpolate(1 << 31), 3 << 19);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Farrow {
+    /// Input samples, newest first
+    x: [i32; 4],
+}
+
+impl Farrow {
+    /// Create a new interpolator from the four samples, newest first.
+    pub fn new(x: [i32; 4]) -> Self {
+        Self { x }
+    }
+
+    /// Ingest a new input sample.
+    pub fn push(&mut self, x: i32) {
+        self.x.copy_within(..3, 1);
+        self.x[0] = x;
+    }
+
+    /// Interpolate at a given fractional delay.
+    ///
+    /// # Args
+    /// * `mu`: Fractional delay in units of `1 << 32` samples
+    ///   in addition to the fixed delay of one sample.
+    ///
+    /// # Returns
+    /// Interpolated sample
+    pub fn interpolate(&self, mu: u32) -> i32 {
+        let [xm, x0, x1, x2] = self.x.map(|x| x as i64);
+        let c1 = (x1 * 6 - xm * 2 - x0 * 3 - x2) / 6;
+        let c2 = (xm + x1 - x0 * 2) / 2;
+        let c3 = (x2 - xm + (x0 - x1) * 3) / 6;
+        // Coefficients have up to 34 bits: reduce the delay resolution
+        // to keep the products within i64.
+        let mu = (mu >> 4) as i64;
+        let y = ((((((((c3 * mu) >> 28) + c2) * mu) >> 28) + c1) * mu) >> 28) + x0;
+        y.clamp(i32::MIN as _, i32::MAX as _) as _
+    }
+
+    /// Ingest a new input sample and interpolate.
+    ///
+    /// See [`Farrow::push()`] and [`Farrow::interpolate()`].
+    pub fn update(&mut self, x: i32, mu: u32) -> i32 {
+        self.push(x);
+        self.interpolate(mu)
+    }
+}
+
+/// Streaming arbitrary ratio resampler
+///
+/// The output sample times are tracked with an accumulator in units of
+/// `1/den` input samples and advance by `num/den` input samples per output
+/// sample. The output samples are interpolated with a [`Farrow`] interpolator.
+///
+/// For rational ratios (`num` input samples for every `den` output samples)
+/// the phase stepping is exact and does not drift.
+/// Arbitrary ratios are approximated with up to `den = 1 << 32`.
+///
+/// For better alias rejection, oversample the input with a
+/// [`Interpolator`] and/or band limit the output with a [`Decimator`] or
+/// [`Fir`].
+///
+/// ```
+/// # use idsp::fir::Resampler;
+/// // 3 input samples for every 2 output samples
+/// let mut r = Resampler::new(3, 2);
+/// let x: Vec<i32> = (0..12).map(|i| i << 20).collect();
+/// let mut y = [0; 8];
+/// let y = r.process(&x, &mut y);
+/// // After the initial transient, resampled ramp
+/// assert_eq!(y[2..], [2 << 20, 7 << 19, 5 << 20, 13 << 19, 8 << 20, 19 << 19]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Resampler {
+    farrow: Farrow,
+    /// Delay of the next output sample with respect to the newest but one input
+    /// sample in units of `1/den` input samples.
+    mu: i64,
+    /// Input samples per output sample in units of `1/den`
+    num: u64,
+    /// Step denominator
+    den: u64,
+}
+
+impl Resampler {
+    /// Create a new resampler.
+    ///
+    /// # Args
+    /// * `num`: Number of input samples for `den` output samples.
+    /// * `den`: Number of output samples for `num` input samples.
+    ///   `1 <= den <= 1 << 32`.
+    pub fn new(num: u64, den: u64) -> Self {
+        debug_assert!(den > 0 && den <= 1 << 32);
+        debug_assert!(num > 0);
+        Self {
+            farrow: Farrow::default(),
+            mu: -(den as i64),
+            num,
+            den,
+        }
+    }
+
+    /// Create an arbitrary ratio resampler.
+    ///
+    /// # Args
+    /// * `ratio`: Input samples per output sample in units of `1 << 32`
+    pub fn with_ratio(ratio: u64) -> Self {
+        Self::new(ratio, 1 << 32)
+    }
+
+    /// Change the ratio.
+    ///
+    /// The output phase is kept.
+    pub fn set_ratio(&mut self, num: u64, den: u64) {
+        debug_assert!(den > 0 && den <= 1 << 32);
+        debug_assert!(num > 0);
+        self.mu = ((self.mu as i128 * den as i128) / self.den as i128) as i64;
+        self.num = num;
+        self.den = den;
+    }
+
+    /// Ingest an input sample and emit the resulting output samples.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `f`: Called with every new output sample
+    pub fn update(&mut self, x: i32, mut f: impl FnMut(i32)) {
+        self.farrow.push(x);
+        self.mu += self.den as i64;
+        while self.mu >= 0 && self.mu < self.den as i64 {
+            f(self
+                .farrow
+                .interpolate((((self.mu as u64) << 32) / self.den) as _));
+            self.mu -= self.num as i64;
+        }
+    }
+
+    /// Process a block of samples
+    ///
+    /// # Args
+    /// * `x`: Input samples
+    /// * `y`: Output sample buffer. Must be large enough to hold all output
+    ///   samples.
+    ///
+    /// # Returns
+    /// The output samples written into `y`.
+    pub fn process<'a>(&mut self, x: &[i32], y: &'a mut [i32]) -> &'a mut [i32] {
+        let mut n = 0;
+        for x in x.iter() {
+            self.update(*x, |yi| {
+                y[n] = yi;
+                n += 1;
+            });
+        }
+        &mut y[..n]
+    }
+}
+
+/// Matched filter
+///
+/// Correlates the input with a template and reports the correlation peak
+/// per block of input samples, e.g. for pulse detection and time of flight
+/// measurement.
+///
+/// The correlation is computed with a [`Fir`] filter. Its taps are the
+/// time-reversed template: for a template `t` (oldest sample first)
+/// the taps are `h[k] = t[N - 1 - k]`. The correlation at a given
+/// sample is the match of the template ending at that sample.
+///
+/// ```
+/// # use idsp::fir::Matched;
+/// let t = [1 << 28, 2 << 28, -(1 << 28)];
+/// let mut h = t;
+/// h.reverse();
+/// let mut m = Matched::<3>::default();
+/// let x = [0, 0, 0, 100, 200, -100, 0, 0];
+/// assert_eq!(m.process(&x, &h), Some((150, 5)));
+/// assert_eq!(m.process(&[], &h), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matched<const N: usize> {
+    fir: Fir<i32, N>,
+}
+
+impl<const N: usize> Default for Matched<N> {
+    fn default() -> Self {
+        Self {
+            fir: Fir::default(),
+        }
+    }
+}
+
+impl<const N: usize> Matched<N> {
+    /// Process a block of samples.
+    ///
+    /// # Args
+    /// * `x`: Input samples
+    /// * `h`: Filter taps, the time-reversed template
+    ///
+    /// # Returns
+    /// The maximum correlation within the block and the index of the
+    /// sample in the block where it occurred (the first if there are multiple).
+    /// `None` if the block is empty.
+    pub fn process(&mut self, x: &[i32], h: &[i32; N]) -> Option<(i32, usize)> {
+        x.iter()
+            .enumerate()
+            .map(|(i, x)| (self.fir.update(*x, h), i))
+            .fold(None, |m, (y, i)| match m {
+                Some((p, _)) if p >= y => m,
+                _ => Some((y, i)),
+            })
+    }
+
+    /// The correlation filter
+    pub fn fir(&self) -> &Fir<i32, N> {
+        &self.fir
+    }
+}
+
+/// Design a windowed Hilbert transformer
+///
+/// The ideal Hilbert transformer impulse response `2/(pi*k)` for odd `k`
+/// (zero for even `k`) truncated to the odd length `N` and multiplied
+/// with the given window. The group delay is `(N - 1)/2` samples.
+/// The taps are antisymmetric.
+///
+/// # Args
+/// * `window`: Window, e.g. from [`crate::window::hamming()`] with
+///   `periodic = false`
+///
+/// # Returns
+/// Taps, newest sample first, suitable for [`Fir`] and [`Analytic`]
+/// after quantizing with [`Coefficient::quantize()`].
+///
+/// ```
+/// # use idsp::fir::hilbert;
+/// let h: [f64; 5] = hilbert(&[1.0; 5]);
+/// let p = 2.0 / core::f64::consts::PI;
+/// assert_eq!(h, [0.0, -p, 0.0, p, 0.0]);
+/// ```
+pub fn hilbert<const N: usize>(window: &[f64; N]) -> [f64; N] {
+    debug_assert!(N & 1 == 1);
+    let m = (N / 2) as isize;
+    let mut h = [0.0; N];
+    for (j, (h, w)) in h.iter_mut().zip(window.iter()).enumerate() {
+        let k = j as isize - m;
+        if k & 1 == 1 {
+            *h = w * 2.0 / (core::f64::consts::PI * k as f64);
+        }
+    }
+    h
+}
+
+/// Analytic signal
+///
+/// Computes the analytic signal `x + i*H(x)` of a real input using a
+/// Hilbert transformer FIR filter (see [`hilbert()`]) and a matching
+/// delay of the real path. The delay is `(N - 1)/2` samples.
+/// The magnitude and argument of the output
+/// (see [`crate::magnitude()`] and [`crate::ComplexExt::arg()`]) are the instantaneous amplitude
+/// and phase of the input.
+///
+/// ```
+/// # use idsp::{cossin, fir::{hilbert, Analytic}, window::hamming, Coefficient};
+/// let h: [f64; 31] = hilbert(&hamming(false));
+/// let h = h.map(i32::quantize);
+/// let mut a = Analytic::<31>::default();
+/// let mut y = Default::default();
+/// for i in 0..40 {
+///     y = a.update(cossin((i as i32) << 29).0 >> 1, &h);
+/// }
+/// // Delayed by 15 samples
+/// let (c, s) = cossin((39 - 15) << 29);
+/// assert!((y.re - (c >> 1)).abs() < 1 << 20);
+/// assert!((y.im - (s >> 1)).abs() < 1 << 23);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Analytic<const N: usize> {
+    fir: Fir<i32, N>,
+}
+
+impl<const N: usize> Default for Analytic<N> {
+    fn default() -> Self {
+        Self {
+            fir: Fir::default(),
+        }
+    }
+}
+
+impl<const N: usize> Analytic<N> {
+    /// Ingest a new sample and return the analytic signal.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `h`: Hilbert transformer taps, odd length
+    pub fn update(&mut self, x: i32, h: &[i32; N]) -> Complex<i32> {
+        let im = self.fir.update(x, h);
+        Complex::new(self.fir.x(N / 2), im)
+    }
+}
+
+/// Design Savitzky-Golay smoothing and differentiating filter taps
+///
+/// The taps evaluate the `deriv`-th derivative of the least squares
+/// polynomial fit of order `M - 1` to the last `N` samples, at the center
+/// of the window. The group delay is `(N - 1)/2` samples.
+/// `deriv = 0` smooths, `deriv = 1` estimates the slope (per sample),
+/// `deriv = 2` the curvature (per sample squared) etc.
+///
+/// # Args
+/// * `deriv`: Derivative order, `deriv < M`.
+///
+/// # Returns
+/// Taps newest sample first, suitable for [`Fir`] after quantizing with
+/// [`Coefficient::quantize()`].
+///
+/// ```
+/// # use idsp::{Coefficient, fir::{savitzky_golay, Fir}};
+/// let h: [f64; 7] = savitzky_golay::<7, 3>(1);
+/// let h = h.map(i32::quantize);
+/// let mut f = Fir::default();
+/// // Slope of a quadratic at the window center
+/// let y: Vec<_> = (0..10).map(|i| f.update(i * i * 100, &h)).collect();
+/// assert_eq!(y[6..], [600, 800, 1000, 1200]);
+/// ```
+pub fn savitzky_golay<const N: usize, const M: usize>(deriv: usize) -> [f64; N] {
+    debug_assert!(deriv < M && M <= N);
+    // Sample time relative to the window center
+    let t = |i: usize| (N - 1) as f64 / 2.0 - i as f64;
+    // Normal equations
+    let mut a = [[0.0; M]; M];
+    for i in 0..N {
+        for (j, aj) in a.iter_mut().enumerate() {
+            for (k, ajk) in aj.iter_mut().enumerate() {
+                *ajk += Float::powi(t(i), (j + k) as _);
+            }
+        }
+    }
+    let mut e = [0.0; M];
+    e[deriv] = (1..=deriv).product::<usize>() as f64;
+    let v = solve(a, e);
+    let mut h = [0.0; N];
+    for (i, h) in h.iter_mut().enumerate() {
+        *h = v
+            .iter()
+            .enumerate()
+            .map(|(j, v)| v * Float::powi(t(i), j as _))
+            .sum();
+    }
+    h
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn impulse() {
+        let h = [1, 2, 3, 4].map(|h| h << 28);
+        let mut f = Fir::<i32, 4>::default();
+        let y: Vec<_> = (0..6).map(|i| f.update((i == 0) as i32 * 8, &h)).collect();
+        assert_eq!(y, [2, 4, 6, 8, 0, 0]);
+        assert_eq!(f.x(5 - 4), 0);
+    }
+
+    #[test]
+    fn filter_get() {
+        let h = [1, 2, 1].map(|h| h << 28);
+        let mut f = Fir::<i32, 3>::default();
+        assert_eq!(Filter::update(&mut f, 8, &h), 2);
+        assert_eq!(Filter::get(&f), 2);
+        Filter::set(&mut f, 8);
+        assert_eq!(Filter::get(&f), Filter::update(&mut f, 8, &h));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "overflow-checks", should_panic)]
+    fn saturate() {
+        let h = [<i32 as Coefficient>::ONE; 3];
+        let mut f = Fir::<i32, 3>::default();
+        f.set(i32::MAX);
+        assert_eq!(f.update(i32::MAX, &h), i32::MAX);
+        f.set(i32::MIN);
+        assert_eq!(f.update(i32::MIN, &h), i32::MIN);
+    }
+
+    #[test]
+    fn symmetric_even() {
+        let h = [1, -2, 3].map(|h| h << 26);
+        let mut f = Symmetric::<i32, 3, 6>::default();
+        let mut g = Fir::<i32, 6>::default();
+        for i in 0..20 {
+            let x = (i * 0x1234567) ^ 0x5a5a5a;
+            assert_eq!(
+                f.update(x, &h),
+                g.update(x, &[h[0], h[1], h[2], h[2], h[1], h[0]])
+            );
+        }
+    }
+
+    #[test]
+    fn decimator() {
+        let h: Vec<i32> = (1..=12).map(|h| h << 24).collect();
+        let hp = polyphase::<_, 3, 4>(&h);
+        let mut d = Decimator::<i32, 3, 4>::default();
+        let mut f = Fir::<i32, 12>::default();
+        let h: [i32; 12] = h.try_into().unwrap();
+        for i in 0..40 {
+            let x = (i * 0x1234567) ^ 0x5a5a5a;
+            let y = f.update(x, &h);
+            if let Some(yd) = d.update(x, &hp) {
+                assert_eq!(yd, y);
+                assert_eq!(i % 3, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn interpolator() {
+        let h: Vec<i32> = (1..=12).map(|h| h << 24).collect();
+        let hp = polyphase::<_, 3, 4>(&h);
+        let mut i = Interpolator::<i32, 3, 4>::default();
+        let mut f = Fir::<i32, 12>::default();
+        let h: [i32; 12] = h.try_into().unwrap();
+        for j in 0..20 {
+            let x = (j * 0x1234567) ^ 0x5a5a5a;
+            let y = i.update(x, &hp);
+            assert_eq!(y[0], f.update(x, &h));
+            assert_eq!(y[1], f.update(0, &h));
+            assert_eq!(y[2], f.update(0, &h));
+        }
+    }
+
+    #[test]
+    fn farrow_cubic() {
+        let p = |t: f64| 3e3 * t * t * t - 2e5 * t * t + 7e6 * t - 5e8;
+        let mut f = Farrow::default();
+        for t in 0..4 {
+            f.push(p(t as f64) as i32);
+        }
+        for i in 0..=16 {
+            let mu = ((i as u64 * (1 << 32)) / 16).min(u32::MAX as _) as u32;
+            let want = p(2.0 - mu as f64 / (1u64 << 32) as f64);
+            let have = f.interpolate(mu) as f64;
+            assert!((have - want).abs() < 4.0, "{i}: {have} != {want}");
+        }
+    }
+
+    #[test]
+    fn resampler_rate() {
+        let mut r = Resampler::new(441, 480);
+        for i in 0..441 {
+            r.update(i, |_| ());
+        }
+        let mut n = 0;
+        for i in 0..441 * 10 {
+            r.update(i, |_| n += 1);
+        }
+        assert_eq!(n, 480 * 10);
+    }
+
+    #[test]
+    fn savitzky_golay_smooth() {
+        let h: [f64; 5] = savitzky_golay::<5, 3>(0);
+        let h0 = [-3.0, 12.0, 17.0, 12.0, -3.0].map(|h| h / 35.0);
+        for (h, h0) in h.iter().zip(h0) {
+            assert!((h - h0).abs() < 1e-12);
+        }
+        let h: [f64; 5] = savitzky_golay::<5, 3>(2);
+        let h0 = [2.0, -1.0, -2.0, -1.0, 2.0].map(|h| h / 7.0);
+        for (h, h0) in h.iter().zip(h0) {
+            assert!((h - h0).abs() < 1e-12);
+        }
+    }
+}
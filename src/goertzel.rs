@@ -0,0 +1,149 @@
+use core::f64::consts::TAU;
+use num_traits::Float;
+
+/// Goertzel single bin DFT
+///
+/// Second order resonator evaluating the DFT of a block of samples at one
+/// arbitrary frequency in O(1) per sample.
+///
+/// ```
+/// # use idsp::{cossin, Goertzel};
+/// let mut g = Goertzel::new(1 << 27);
+/// for i in 0..256 {
+///     g.update(cossin((i as i32) << 27).0 >> 1);
+/// }
+/// // |X| = A*N/2
+/// let a = g.power().sqrt() * 2.0 / 256.0;
+/// assert!((a / (1u64 << 30) as f64 - 1.0).abs() < 1e-4);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Goertzel {
+    coeff: f64,
+    s: [f64; 2],
+}
+
+impl Goertzel {
+    /// Create a new Goertzel bin
+    ///
+    /// # Args
+    /// * `frequency`: Bin frequency, full turn per sample is `1 << 32`
+    pub fn new(frequency: u32) -> Self {
+        Self {
+            coeff: 2.0 * Float::cos(TAU * frequency as f64 / (1u64 << 32) as f64),
+            s: [0.0; 2],
+        }
+    }
+
+    /// Ingest a new sample.
+    pub fn update(&mut self, x: i32) {
+        let s = x as f64 + self.coeff * self.s[0] - self.s[1];
+        self.s = [s, self.s[0]];
+    }
+
+    /// Squared DFT magnitude of the samples since the last reset
+    pub fn power(&self) -> f64 {
+        let [s0, s1] = self.s;
+        s0 * s0 + s1 * s1 - self.coeff * s0 * s1
+    }
+
+    /// Clear the state to start a new block.
+    pub fn reset(&mut self) {
+        self.s = [0.0; 2];
+    }
+}
+
+/// Total harmonic distortion estimator
+///
+/// Measures the amplitudes of the fundamental and the first `K - 1`
+/// harmonics with a bank of [`Goertzel`] bins over blocks of samples
+/// and computes the total harmonic distortion
+/// `sqrt(sum(A_h**2, h = 2..=K))/A_1`.
+///
+/// Harmonics beyond Nyquist alias. Leakage is minimal if the block
+/// length is an integer number of fundamental periods.
+///
+/// ```
+/// # use idsp::{cossin, Thd};
+/// let mut t = Thd::<4>::new(1 << 26, 1024);
+/// let mut thd = None;
+/// for i in 0..1024 {
+///     let p = (i as i32) << 26;
+///     // 1 % second and 2 % third harmonic
+///     let x = (cossin(p).0 >> 1) + cossin(p.wrapping_mul(2)).0 / 200
+///         + cossin(p.wrapping_mul(3)).1 / 100;
+///     thd = t.update(x);
+/// }
+/// let thd = thd.unwrap();
+/// assert!((thd - 5e-4f64.sqrt()).abs() < 1e-5);
+/// let a = t.amplitudes();
+/// assert!((a[0] / (1u64 << 30) as f64 - 1.0).abs() < 1e-4);
+/// assert!((a[2] / a[0] - 0.02).abs() < 1e-5);
+/// assert!(a[3] / a[0] < 1e-5);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Thd<const K: usize> {
+    bins: [Goertzel; K],
+    len: u32,
+    count: u32,
+    amplitudes: [f64; K],
+}
+
+impl<const K: usize> Thd<K> {
+    /// Create a new THD estimator
+    ///
+    /// # Args
+    /// * `frequency`: Fundamental frequency, full turn per sample is `1 << 32`
+    /// * `len`: Block length in samples
+    pub fn new(frequency: u32, len: u32) -> Self {
+        debug_assert!(K > 0 && len > 0);
+        Self {
+            bins: core::array::from_fn(|h| Goertzel::new(frequency.wrapping_mul(h as u32 + 1))),
+            len,
+            count: 0,
+            amplitudes: [0.0; K],
+        }
+    }
+
+    /// Ingest a new sample.
+    ///
+    /// # Returns
+    /// The total harmonic distortion at the end of each block,
+    /// `None` otherwise and if the fundamental vanishes.
+    pub fn update(&mut self, x: i32) -> Option<f64> {
+        for b in self.bins.iter_mut() {
+            b.update(x);
+        }
+        self.count += 1;
+        if self.count < self.len {
+            return None;
+        }
+        self.count = 0;
+        let scale = 2.0 / self.len as f64;
+        for (a, b) in self.amplitudes.iter_mut().zip(self.bins.iter_mut()) {
+            *a = Float::sqrt(b.power()) * scale;
+            b.reset();
+        }
+        self.thd()
+    }
+
+    /// Amplitudes of the fundamental and the harmonics in the last block
+    pub fn amplitudes(&self) -> &[f64; K] {
+        &self.amplitudes
+    }
+
+    /// Total harmonic distortion of the last block
+    ///
+    /// `None` if the fundamental vanishes.
+    pub fn thd(&self) -> Option<f64> {
+        let (a1, h) = self.amplitudes.split_first()?;
+        (*a1 > 0.0).then(|| Float::sqrt(h.iter().map(|a| a * a).sum::<f64>()) / a1)
+    }
+
+    /// Discard the current partial block.
+    pub fn reset(&mut self) {
+        self.count = 0;
+        for b in self.bins.iter_mut() {
+            b.reset();
+        }
+    }
+}
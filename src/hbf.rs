@@ -541,6 +541,76 @@ impl Filter for HbfDecCascade {
     }
 }
 
+/// Decimation filter cascade of two stages
+///
+/// The high rate stage `H` is applied first, then the low rate stage `L`.
+/// Nest `DecCascade` to build cascades of arbitrary depth with
+/// arbitrary (e.g. application specific) taps and types.
+/// In contrast to [`HbfDecCascade`], the depth is fixed at compile time
+/// but out-of-place processing is supported.
+///
+/// ```
+/// # use idsp::hbf::*;
+/// let mut h = DecCascade::new(
+///     HbfDec::<_, { HBF_TAPS.2.len() }, { 2 * HBF_TAPS.2.len() - 1 + 16 }>::new(&HBF_TAPS.2),
+///     DecCascade::new(
+///         HbfDec::<_, { HBF_TAPS.1.len() }, { 2 * HBF_TAPS.1.len() - 1 + 8 }>::new(&HBF_TAPS.1),
+///         HbfDec::<_, { HBF_TAPS.0.len() }, { 2 * HBF_TAPS.0.len() - 1 + 4 }>::new(&HBF_TAPS.0),
+///     ),
+/// );
+/// assert_eq!(h.block_size(), (8, 32));
+/// let x = [1.0; 32];
+/// let mut y = [0.0; 32];
+/// assert_eq!(h.process_block(Some(&x), &mut y).len(), 4);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct DecCascade<H, L> {
+    high: H,
+    low: L,
+}
+
+impl<H, L> DecCascade<H, L> {
+    /// Create a new cascade from a high rate and a low rate stage.
+    pub fn new(high: H, low: L) -> Self {
+        Self { high, low }
+    }
+
+    /// The high rate stage
+    pub fn high(&mut self) -> &mut H {
+        &mut self.high
+    }
+
+    /// The low rate stage
+    pub fn low(&mut self) -> &mut L {
+        &mut self.low
+    }
+}
+
+impl<H: Filter, L: Filter<Item = H::Item>> Filter for DecCascade<H, L> {
+    type Item = H::Item;
+
+    #[inline]
+    fn block_size(&self) -> (usize, usize) {
+        let (gh, mh) = self.high.block_size();
+        let (gl, ml) = self.low.block_size();
+        (gh * gl, mh.min(ml.saturating_mul(gh)))
+    }
+
+    #[inline]
+    fn response_length(&self) -> usize {
+        self.high.response_length() / self.low.block_size().0 + self.low.response_length()
+    }
+
+    fn process_block<'a>(
+        &mut self,
+        x: Option<&[Self::Item]>,
+        y: &'a mut [Self::Item],
+    ) -> &'a mut [Self::Item] {
+        let y = self.high.process_block(x, y);
+        self.low.process_block(None, y)
+    }
+}
+
 /// Half-band interpolation filter cascade with optimal taps.
 ///
 /// This is a no_alloc version without trait objects.
@@ -710,6 +780,30 @@ mod test {
         println!("{:?}", x);
     }
 
+    #[test]
+    fn dec_cascade() {
+        let mut h = HbfDecCascade::default();
+        h.set_depth(2);
+        let mut c = DecCascade::new(
+            HbfDec::<_, { HBF_TAPS.1.len() }, { 2 * HBF_TAPS.1.len() - 1 + HBF_CASCADE_BLOCK * 2 }>::new(
+                &HBF_TAPS.1,
+            ),
+            HbfDec::<_, { HBF_TAPS.0.len() }, { 2 * HBF_TAPS.0.len() - 1 + HBF_CASCADE_BLOCK }>::new(
+                &HBF_TAPS.0,
+            ),
+        );
+        assert_eq!(h.block_size(), c.block_size());
+        assert_eq!(h.response_length(), c.response_length());
+        for _ in 0..4 {
+            let x: Vec<f32> = (0..1 << 8).map(|_| rand::random()).collect();
+            let mut y = x.clone();
+            let yh = h.process_block(None, &mut y);
+            let mut z = vec![0.0; x.len()];
+            let yc = c.process_block(Some(&x), &mut z);
+            assert_eq!(yh, yc);
+        }
+    }
+
     #[test]
     fn response_length_dec() {
         let mut h = HbfDecCascade::default();
@@ -733,7 +827,7 @@ mod test {
         );
         let k = h.block_size().0;
         let r = h.response_length();
-        let mut x = vec![0.0; (r + 1 + k - 1) / k * k];
+        let mut x = vec![0.0; (r + 1).div_ceil(k) * k];
         x[0] = 1.0;
         let x = h.process_block(None, &mut x);
         println!("{:?}", x); // interpolator impulse response
@@ -0,0 +1,181 @@
+/// Hysteresis comparator (Schmitt trigger)
+///
+/// The output turns on when the input exceeds the upper threshold and turns
+/// off when the input falls below the lower threshold. In between, the
+/// output is held. Noise smaller than the hysteresis band
+/// (`high - low`) does not cause the output to chatter.
+///
+/// ```
+/// # use idsp::Hysteresis;
+/// let mut h = Hysteresis::default();
+/// let y: Vec<_> = [0, 5, 11, 5, -1, -11, 0]
+///     .iter()
+///     .map(|x| h.update(*x, -10, 10))
+///     .collect();
+/// assert_eq!(y, [false, false, true, true, true, false, false]);
+/// assert_eq!(h.sign(), -1);
+/// assert_eq!(h.count(), 2);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hysteresis {
+    state: bool,
+    count: u32,
+}
+
+impl Hysteresis {
+    /// Create a new comparator in the given state.
+    pub fn new(state: bool) -> Self {
+        Self { state, count: 0 }
+    }
+
+    /// Ingest a new sample
+    ///
+    /// # Args
+    /// * `x`: New sample
+    /// * `low`: Lower threshold. The output turns off for `x < low`.
+    /// * `high`: Upper threshold. The output turns on for `x > high`.
+    ///   `low <= high`.
+    ///
+    /// # Returns
+    /// The new output state.
+    pub fn update<T: PartialOrd>(&mut self, x: T, low: T, high: T) -> bool {
+        debug_assert!(low <= high);
+        let state = if self.state { x >= low } else { x > high };
+        if state != self.state {
+            self.state = state;
+            self.count = self.count.wrapping_add(1);
+        }
+        self.state
+    }
+
+    /// The current output state
+    pub fn get(&self) -> bool {
+        self.state
+    }
+
+    /// The current output state as `1` (on) or `-1` (off)
+    pub fn sign(&self) -> i32 {
+        if self.state {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Number of output transitions (wrapping), e.g. for fringe counting
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Set the output state.
+    pub fn set(&mut self, state: bool) {
+        self.state = state;
+    }
+}
+
+/// Zero crossing period and frequency counter
+///
+/// Measures the period between rising zero crossings of the input.
+/// The crossing times are linearly interpolated between samples.
+/// A crossing is only counted after the input has fallen below `-hysteresis`
+/// to reject noise around zero.
+///
+/// ```
+/// # use idsp::{cossin, ZeroCrossing};
+/// let mut z = ZeroCrossing::default();
+/// let f = 0x0123_4567i32; // frequency word
+/// let mut p = None;
+/// for i in 0..1000 {
+///     let x = cossin(f.wrapping_mul(i)).1 >> 1;
+///     p = z.update(x, 1 << 20).or(p);
+/// }
+/// // Period in samples with 32 fractional bits
+/// let p0 = (1u128 << 64) / f as u128;
+/// assert!((p.unwrap() as i64 - p0 as i64).abs() < 1 << 20);
+/// assert!((z.frequency() - f).abs() < 1 << 8);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ZeroCrossing {
+    /// Time of the previous sample, 32 fractional bits, wrapping
+    time: u64,
+    /// Previous sample
+    x: i32,
+    /// Time of the last crossing
+    last: Option<u64>,
+    /// Below the negative threshold since the last crossing
+    armed: bool,
+    /// Last period
+    period: u64,
+}
+
+impl ZeroCrossing {
+    /// Ingest a new sample
+    ///
+    /// # Args
+    /// * `x`: New sample
+    /// * `hysteresis`: Arming threshold, `hysteresis >= 0`
+    ///
+    /// # Returns
+    /// The period in samples (32 fractional bits) on each rising zero
+    /// crossing after the first.
+    pub fn update(&mut self, x: i32, hysteresis: i32) -> Option<u64> {
+        let mut period = None;
+        if self.armed && self.x < 0 && x >= 0 {
+            let dx = (x as i64 - self.x as i64) as u64;
+            let t = self
+                .time
+                .wrapping_add(((self.x.unsigned_abs() as u64) << 32) / dx);
+            if let Some(last) = self.last {
+                self.period = t.wrapping_sub(last);
+                period = Some(self.period);
+            }
+            self.last = Some(t);
+            self.armed = false;
+        }
+        if x < hysteresis.saturating_neg() {
+            self.armed = true;
+        }
+        self.x = x;
+        self.time = self.time.wrapping_add(1 << 32);
+        period
+    }
+
+    /// The last period in samples with 32 fractional bits, zero if none
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// The frequency word from the last period (full turn per sample is `1 << 32`),
+    /// zero if none
+    pub fn frequency(&self) -> i32 {
+        if self.period == 0 {
+            0
+        } else {
+            ((1u128 << 64) / self.period as u128) as u32 as i32
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cossin;
+
+    #[test]
+    fn zero_crossing_wrap() {
+        let mut z = ZeroCrossing {
+            time: u64::MAX - (500 << 32),
+            ..Default::default()
+        };
+        let f = 0x0123_4567i32;
+        let p0 = ((1u128 << 64) / f as u128) as i64;
+        let mut n = 0;
+        for i in 0..2000 {
+            if let Some(p) = z.update(cossin(f.wrapping_mul(i)).1 >> 1, 1 << 20) {
+                assert!((p as i64 - p0).abs() < 1 << 20, "{i} {p}");
+                n += 1;
+            }
+        }
+        assert!(n > 3);
+    }
+}
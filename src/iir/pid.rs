@@ -79,7 +79,7 @@ impl<T: Float> Pid<T> {
     /// * `input` are input (`x`) units
     /// * `time` are sample period units, e.g. SI seconds
     /// * `order` is the action order: the frequency exponent
-    ///    (`-1` for integrating, `0` for proportional, etc.)
+    ///   (`-1` for integrating, `0` for proportional, etc.)
     ///
     /// Note that inverse time units correspond to angular frequency units.
     /// Gains are accurate in the low frequency limit. Towards Nyquist, the
@@ -222,6 +222,7 @@ mod test {
     use crate::iir::*;
 
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn pid() {
         let b: Biquad<f32> = Pid::default()
             .period(1.0)
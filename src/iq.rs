@@ -0,0 +1,230 @@
+use num_traits::Float;
+
+use crate::{Complex, Ewma, EwmaVariance, Filter, LeakyIntegrator};
+
+/// Correct I/Q gain and phase imbalance
+///
+/// `I' = I, Q' = a*I + b*Q`
+///
+/// For a quadrature component with relative gain `g` and phase error
+/// `phi`: `a = -tan(phi)`, `b = 1/(g*cos(phi))`. See
+/// [`IqImbalance::correction()`] for an estimator.
+///
+/// # Args
+/// * `iq`: Input sample
+/// * `k`: Correction coefficients `[a, b]` in Q30 (`1 << 30` is unity)
+///
+/// ```
+/// # use idsp::{iq_balance, Complex};
+/// let iq = Complex::new(1 << 20, 3 << 20);
+/// let k = [-(1 << 29), 1 << 29];
+/// assert_eq!(iq_balance(iq, &k), Complex::new(1 << 20, 1 << 20));
+/// ```
+pub fn iq_balance(iq: Complex<i32>, k: &[i32; 2]) -> Complex<i32> {
+    let q = (k[0] as i64 * iq.re as i64 + k[1] as i64 * iq.im as i64) >> 30;
+    Complex::new(iq.re, q.clamp(i32::MIN as _, i32::MAX as _) as _)
+}
+
+/// I/Q imbalance estimator
+///
+/// Tracks the second moments `E[I*I]`, `E[Q*Q]`, `E[I*Q]` with
+/// exponential averaging (see [`Ewma`]) and derives the correction
+/// coefficients for [`iq_balance()`]. The signal should be
+/// uniformly distributed in phase (e.g. a tone offset from DC).
+///
+/// ```
+/// # use idsp::{iq_balance, Complex, IqImbalance};
+/// let (g, phi) = (1.2f64, 0.1f64);
+/// let mut e = IqImbalance::default();
+/// for i in 0..50_000 {
+///     let t = i as f64 * 0.9;
+///     let iq = Complex::new(
+///         ((1 << 29) as f64 * t.cos()) as i32,
+///         ((1 << 29) as f64 * g * (t + phi).sin()) as i32,
+///     );
+///     e.update(iq, 1 << 20);
+/// }
+/// let k = e.correction();
+/// assert!((k[0] as f64 / (1 << 30) as f64 + phi.tan()).abs() < 1e-3);
+/// assert!((k[1] as f64 / (1 << 30) as f64 - 1.0 / (g * phi.cos())).abs() < 1e-3);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct IqImbalance {
+    ii: Ewma,
+    qq: Ewma,
+    iq: Ewma,
+}
+
+impl IqImbalance {
+    /// Ingest a new sample.
+    ///
+    /// # Args
+    /// * `iq`: Input sample
+    /// * `alpha`: Averaging weight, see [`Ewma`]
+    pub fn update(&mut self, iq: Complex<i32>, alpha: u32) {
+        let (i, q) = (iq.re as i64, iq.im as i64);
+        // `i32::MIN**2 >> 32` fits in i32
+        self.ii.update(((i * i) >> 32) as i32, &alpha);
+        self.qq.update(((q * q) >> 32) as i32, &alpha);
+        self.iq.update(((i * q) >> 32) as i32, &alpha);
+    }
+
+    /// Relative gain `g` and phase error `sin(phi)` of the quadrature component
+    pub fn imbalance(&self) -> (f64, f64) {
+        let (ii, qq, iq) = (
+            self.ii.get() as f64,
+            self.qq.get() as f64,
+            self.iq.get() as f64,
+        );
+        (Float::sqrt(qq / ii), iq / Float::sqrt(ii * qq))
+    }
+
+    /// Correction coefficients `[a, b]` for [`iq_balance()`]
+    pub fn correction(&self) -> [i32; 2] {
+        let (g, s) = self.imbalance();
+        let c = Float::sqrt(1.0 - s * s);
+        [-s / c, 1.0 / (g * c)].map(|k| (k * (1 << 30) as f64) as i32)
+    }
+}
+
+/// Complex baseband DC offset remover
+///
+/// Tracks the DC offset of I and Q with a [`LeakyIntegrator`] each
+/// and subtracts it, e.g. to remove LO leakage before magnitude and phase
+/// computation. Tracking can be frozen while the offset estimate continues to
+/// be subtracted (e.g. while the signal of interest is near DC).
+///
+/// ```
+/// # use idsp::{Complex, IqDcBlock};
+/// let mut d = IqDcBlock::default();
+/// for _ in 0..1000 {
+///     d.update(Complex::new(500, -300), 6);
+/// }
+/// assert_eq!(d.dc(6), Complex::new(500, -300));
+/// assert_eq!(d.update(Complex::new(600, -300), 6), Complex::new(99, 0));
+/// d.set_hold(true);
+/// assert_eq!(d.update(Complex::new(0, 0), 6), Complex::new(-501, 300));
+/// assert_eq!(d.dc(6), Complex::new(501, -300));
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct IqDcBlock {
+    dc: [LeakyIntegrator; 2],
+    hold: bool,
+}
+
+impl IqDcBlock {
+    /// Ingest a new sample and return it with the DC offset removed.
+    ///
+    /// # Args
+    /// * `iq`: Input sample
+    /// * `k`: Leak shift, the time constant is `1 << k` samples, `k <= 32`
+    pub fn update(&mut self, iq: Complex<i32>, k: u32) -> Complex<i32> {
+        if !self.hold {
+            self.dc[0].update(iq.re, &k);
+            self.dc[1].update(iq.im, &k);
+        }
+        let dc = self.dc(k);
+        Complex::new(iq.re.saturating_sub(dc.re), iq.im.saturating_sub(dc.im))
+    }
+
+    /// The current DC offset estimate
+    ///
+    /// # Args
+    /// * `k`: Leak shift
+    pub fn dc(&self, k: u32) -> Complex<i32> {
+        let [i, q] = self.dc.map(|dc| (dc.sum() >> k) as i32);
+        Complex::new(i, q)
+    }
+
+    /// Freeze (`true`) or resume (`false`) offset tracking.
+    pub fn set_hold(&mut self, hold: bool) {
+        self.hold = hold;
+    }
+
+    /// Whether offset tracking is frozen
+    pub fn hold(&self) -> bool {
+        self.hold
+    }
+}
+
+/// Signal to noise ratio estimator for complex baseband
+///
+/// Tracks the exponentially weighted mean and variance of I and Q
+/// (see [`EwmaVariance`]) of a demodulated signal.
+/// The signal power is the squared magnitude of the mean,
+/// the noise power is the sum of the variances.
+/// Note that the variance estimate settles only after many time constants
+/// following large changes of the mean.
+///
+/// ```
+/// # use idsp::{Complex, IqSnr, Xoshiro128};
+/// let mut s = IqSnr::default();
+/// let mut rng = Xoshiro128::new(1);
+/// for _ in 0..1 << 16 {
+///     // Uniform noise, rms 1/sqrt(3) << 10 per component
+///     let n = Complex::new(rng.update() >> 21, rng.update() >> 21);
+///     s.update(Complex::new(3 << 20, 4 << 20) + n, 10);
+/// }
+/// // 10*log10((25 << 40)/(2/3 << 20)) = 75.9 dB
+/// assert!((s.snr_db() - (759 << 8) / 10).abs() < 1 << 7);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IqSnr {
+    iq: [EwmaVariance; 2],
+}
+
+impl IqSnr {
+    /// Ingest a new IQ sample.
+    ///
+    /// # Args
+    /// * `iq`: IQ sample
+    /// * `k`: Averaging time constant `1 << k` samples, see [`EwmaVariance`]
+    pub fn update(&mut self, iq: Complex<i32>, k: u32) {
+        self.iq[0].update(iq.re, k);
+        self.iq[1].update(iq.im, k);
+    }
+
+    /// The signal power (squared magnitude of the mean)
+    pub fn signal(&self) -> u64 {
+        self.iq
+            .iter()
+            .map(|iq| (iq.mean() as i64).pow(2) as u64)
+            .sum()
+    }
+
+    /// The noise power (sum of the I and Q variances)
+    pub fn noise(&self) -> u64 {
+        self.iq.iter().map(|iq| iq.variance()).sum()
+    }
+
+    /// The signal to noise ratio in dB with 8 fractional bits
+    ///
+    /// Saturates to `i32::MAX` for zero noise and `i32::MIN` for zero
+    /// signal.
+    pub fn snr_db(&self) -> i32 {
+        let (s, n) = (self.signal(), self.noise());
+        if n == 0 {
+            i32::MAX
+        } else if s == 0 {
+            i32::MIN
+        } else {
+            (10.0 * 256.0 * (s as f64 / n as f64).log10()).round() as i32
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn imbalance_full_scale() {
+        let mut e = IqImbalance::default();
+        for _ in 0..1000 {
+            e.update(Complex::new(i32::MIN, i32::MIN), 1 << 24);
+        }
+        assert!(e.ii.get() > 0 && e.qq.get() > 0 && e.iq.get() > 0);
+        let (g, s) = e.imbalance();
+        assert!((g - 1.0).abs() < 1e-6 && (s - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,218 @@
+use num_traits::Float;
+
+use crate::Filter;
+
+/// [`Kalman`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct KalmanConfig<F> {
+    /// Process noise variance per sample
+    pub q: F,
+    /// Measurement noise variance
+    pub r: F,
+}
+
+/// Scalar Kalman filter
+///
+/// Estimates a quantity modeled as a random walk with process noise variance
+/// `q` per sample from measurements with noise variance `r`.
+/// The gain adapts from fast initial convergence to the steady state
+/// determined by `q/r`.
+///
+/// ```
+/// # use idsp::{Kalman, KalmanConfig};
+/// let k = KalmanConfig { q: 1e-4, r: 1.0 };
+/// let mut f = Kalman::<f64>::new(0.0, 100.0);
+/// for i in 0..1000 {
+///     f.update(if i & 1 == 0 { 11.0 } else { 9.0 }, &k);
+/// }
+/// assert!((f.get() - 10.0).abs() < 0.2);
+/// assert!((f.variance() - 0.00995).abs() < 1e-4);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Kalman<F> {
+    /// State estimate
+    x: F,
+    /// Estimate variance
+    p: F,
+}
+
+impl<F: Float> Kalman<F> {
+    /// Create a new filter.
+    ///
+    /// # Args
+    /// * `x`: Initial estimate
+    /// * `p`: Initial estimate variance
+    pub fn new(x: F, p: F) -> Self {
+        Self { x, p }
+    }
+
+    /// Update with a new measurement.
+    ///
+    /// # Args
+    /// * `z`: Measurement
+    /// * `k`: Noise configuration
+    ///
+    /// # Returns
+    /// The new estimate
+    pub fn update(&mut self, z: F, k: &KalmanConfig<F>) -> F {
+        let p = self.p + k.q;
+        let g = p / (p + k.r);
+        self.x = self.x + g * (z - self.x);
+        self.p = (F::one() - g) * p;
+        self.x
+    }
+
+    /// The current estimate
+    pub fn get(&self) -> F {
+        self.x
+    }
+
+    /// The current estimate variance
+    pub fn variance(&self) -> F {
+        self.p
+    }
+}
+
+/// Fixed point scalar Kalman filter
+///
+/// Like [`Kalman`] for `i32` samples. The estimate has 32 fractional bits.
+/// The process and measurement noise variances in the configuration
+/// `[q, r]` and the estimate variance are in units of the
+/// squared input LSB with 16 fractional bits.
+///
+/// ```
+/// # use idsp::{Filter, FixedKalman};
+/// let k = [1 << 8, 100 << 16]; // q = 1/256, r = 100
+/// let mut f = FixedKalman::default();
+/// for i in 0..1000 {
+///     f.update(if i & 1 == 0 { 1010 } else { 990 }, &k);
+/// }
+/// assert!((f.get() - 1000).abs() <= 2);
+/// assert!((f.variance() as i64 - (63 << 16) / 100).abs() < 1 << 10);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FixedKalman {
+    /// State estimate with 32 fractional bits
+    x: i64,
+    /// Estimate variance with 16 fractional bits
+    p: u64,
+}
+
+impl Default for FixedKalman {
+    /// Zero estimate with a large variance
+    fn default() -> Self {
+        Self {
+            x: 0,
+            p: u64::MAX >> 1,
+        }
+    }
+}
+
+impl FixedKalman {
+    /// The current estimate variance
+    pub fn variance(&self) -> u64 {
+        self.p
+    }
+
+    /// Set the estimate variance.
+    pub fn set_variance(&mut self, p: u64) {
+        self.p = p;
+    }
+}
+
+impl Filter for FixedKalman {
+    /// Process and measurement noise variance `[q, r]`
+    type Config = [u64; 2];
+
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        let p = self.p.saturating_add(k[0]) as u128;
+        // Gain with 32 fractional bits
+        let g = ((p << 32) / (p + k[1] as u128).max(1)) as i128;
+        let d = ((x as i64) << 32) as i128 - self.x as i128;
+        self.x += ((d * g) >> 32) as i64;
+        self.p = ((p * ((1 << 32) - g as u128)) >> 32) as u64;
+        self.get()
+    }
+
+    fn get(&self) -> i32 {
+        (self.x >> 32) as _
+    }
+
+    fn set(&mut self, x: i32) {
+        self.x = (x as i64) << 32;
+    }
+}
+
+/// Alpha-beta-gamma tracking filter
+///
+/// Estimates position, velocity and acceleration from noisy position
+/// samples with fixed gains. A lighter alternative to a Kalman filter
+/// for tracking and drift prediction.
+/// With `gamma = 0` this is an alpha-beta filter (constant velocity model).
+///
+/// The configuration `[alpha, beta, gamma]` contains the gains in units
+/// of `1 << 32`. Position, velocity (per sample) and acceleration
+/// (per sample squared) have 32 fractional bits.
+///
+/// ```
+/// # use idsp::{AlphaBetaGamma, Filter};
+/// let k = [1 << 30, 1 << 27, 0];
+/// let mut f = AlphaBetaGamma::default();
+/// for i in 0..1000 {
+///     f.update(7 * i + (i & 1), &k);
+/// }
+/// assert!((f.velocity() - (7 << 32)).abs() < 1 << 28);
+/// assert!((f.predict(10) - (7 * 1009)).abs() <= 1);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AlphaBetaGamma {
+    x: i64,
+    v: i64,
+    a: i64,
+}
+
+impl AlphaBetaGamma {
+    /// The current velocity estimate with 32 fractional bits
+    pub fn velocity(&self) -> i64 {
+        self.v
+    }
+
+    /// The current acceleration estimate with 32 fractional bits
+    pub fn acceleration(&self) -> i64 {
+        self.a
+    }
+
+    /// Predict the position `n` samples ahead.
+    pub fn predict(&self, n: i32) -> i32 {
+        let n = n as i128;
+        let x = self.x as i128 + n * self.v as i128 + ((n * n * self.a as i128) >> 1);
+        (x >> 32).clamp(i32::MIN as _, i32::MAX as _) as _
+    }
+}
+
+impl Filter for AlphaBetaGamma {
+    /// Gains `[alpha, beta, gamma]` in units of `1 << 32`
+    type Config = [u32; 3];
+
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        let xp = self.x + self.v + (self.a >> 1);
+        let vp = self.v + self.a;
+        let r = (((x as i64) << 32) - xp) as i128;
+        self.x = xp + ((r * k[0] as i128) >> 32) as i64;
+        self.v = vp + ((r * k[1] as i128) >> 32) as i64;
+        self.a += ((r * k[2] as i128) >> 31) as i64;
+        self.get()
+    }
+
+    fn get(&self) -> i32 {
+        (self.x >> 32) as _
+    }
+
+    fn set(&mut self, x: i32) {
+        *self = Self {
+            x: (x as i64) << 32,
+            v: 0,
+            a: 0,
+        };
+    }
+}
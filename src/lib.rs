@@ -5,32 +5,97 @@
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
 
+/// `debug_assert!()` only with the `overflow-checks` feature
+macro_rules! overflow_check {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "overflow-checks")]
+        debug_assert!($($arg)*);
+    };
+}
+
+mod adaptive;
+pub use adaptive::*;
+mod allpass;
+pub use allpass::*;
 mod atan2;
 pub use atan2::*;
 mod accu;
 pub use accu::*;
+mod cic;
+pub use cic::*;
+mod clip;
+pub use clip::*;
+mod fft;
+pub use fft::*;
 mod filter;
 pub use filter::*;
+mod comb;
+pub use comb::*;
 mod complex;
 pub use complex::*;
 mod cossin;
 pub use cossin::*;
+mod debounce;
+pub use debounce::*;
+mod delay;
+pub use delay::*;
+mod demod;
+pub use demod::*;
+mod goertzel;
+pub use goertzel::*;
+mod hysteresis;
+pub use hysteresis::*;
 pub mod iir;
+mod iq;
+pub use iq::*;
+mod kalman;
+pub use kalman::*;
 mod lockin;
 pub use lockin::*;
 mod lowpass;
 pub use lowpass::*;
+mod median;
+pub use median::*;
+mod minmax;
+pub use minmax::*;
+mod moving_average;
+pub use moving_average::*;
+mod noise;
+pub use noise::*;
+mod peak;
+pub use peak::*;
+mod phase;
+pub use phase::*;
 mod pll;
 pub use pll::*;
+mod prbs;
+pub use prbs::*;
+mod profile;
+pub use profile::*;
 mod rpll;
 pub use rpll::*;
+mod sdft;
+pub use sdft::*;
+mod slew;
+pub use slew::*;
+mod stats;
+pub use stats::*;
+mod synth;
+pub use synth::*;
 mod unwrap;
 pub use unwrap::*;
+mod welch;
+pub use welch::*;
+mod xcorr;
+pub use xcorr::*;
+pub mod control;
+pub mod fir;
 pub mod hbf;
 mod num;
 pub use num::*;
 mod dsm;
 pub mod svf;
+pub mod window;
 pub use dsm::*;
 
 #[cfg(test)]
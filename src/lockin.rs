@@ -1,4 +1,4 @@
-use super::{Complex, ComplexExt, Filter, MulScaled};
+use super::{magnitude, CicDecimator, Complex, ComplexExt, Filter, MulScaled};
 
 /// Lockin filter
 ///
@@ -26,4 +26,429 @@ impl<T: Filter> Lockin<T> {
         // Get the LO signal for demodulation and mix the sample;
         self.update_iq(sample, Complex::from_angle(phase), k)
     }
+
+    /// Update the lockin with a sample held for `n` sample periods.
+    ///
+    /// See [`Filter::update_n()`]. The mixer output is held.
+    pub fn update_n(&mut self, sample: i32, phase: i32, n: u32, k: &T::Config) -> Complex<i32> {
+        let mix = Complex::from_angle(phase).mul_scaled(sample);
+        Complex {
+            re: self.state[0].update_n(mix.re, n, k),
+            im: self.state[1].update_n(mix.im, n, k),
+        }
+    }
+}
+
+/// [`LockinNco`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockinNcoConfig<C> {
+    /// NCO frequency: phase increment per input sample, full turn is `1 << 32`
+    pub frequency: i32,
+    /// Demodulation phase offset, full turn is `1 << 32`
+    pub phase: i32,
+    /// Decimation rate, `rate > 0`
+    pub rate: u32,
+    /// Lowpass filter configuration
+    pub filter: C,
+}
+
+/// Complete lock-in amplifier
+///
+/// Combines a numerically controlled oscillator (NCO), the complex mixer
+/// and lowpass filters of [`Lockin`], and decimation of the filtered
+/// in-phase and quadrature components.
+///
+/// The lowpass filters run at the input rate, the output is decimated.
+/// The amplitude of the output is half the input amplitude (the other half
+/// is at twice the NCO frequency and rejected by the lowpass).
+///
+/// ```
+/// # use idsp::{cossin, LockinNco, LockinNcoConfig, Lowpass};
+/// let k = LockinNcoConfig {
+///     frequency: 1 << 28, // 1/16 of the sample rate
+///     phase: 0,
+///     rate: 4,
+///     filter: [1 << 12, -0x5a_8279], // Butterworth, f0 = 2e-4 fs
+/// };
+/// let mut l = LockinNco::<Lowpass<2>>::default();
+/// let mut iq = None;
+/// for i in 0..10_000 {
+///     // Input with amplitude 1/2 full scale, phase pi/2: -sin(2 pi f i)/2
+///     let x = cossin(((i as i32) << 28).wrapping_add(1 << 30)).0 >> 1;
+///     if let Some(y) = l.update(x, &k) {
+///         iq = Some(y);
+///     }
+/// }
+/// let iq = iq.unwrap();
+/// assert!(iq.re.abs() < 1 << 12);
+/// assert!((iq.im + (1 << 29)).abs() < 1 << 16);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct LockinNco<T> {
+    lockin: Lockin<T>,
+    phase: i32,
+    index: u32,
+}
+
+impl<T: Filter> LockinNco<T> {
+    /// Ingest a new sample.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `k`: Configuration
+    ///
+    /// # Returns
+    /// The demodulated in-phase and quadrature components every `rate`-th
+    /// sample.
+    pub fn update(&mut self, x: i32, k: &LockinNcoConfig<T::Config>) -> Option<Complex<i32>> {
+        let iq = self
+            .lockin
+            .update(x, self.phase.wrapping_add(k.phase), &k.filter);
+        self.phase = self.phase.wrapping_add(k.frequency);
+        let index = self.index;
+        self.index = if index == 0 { k.rate - 1 } else { index - 1 };
+        (index == 0).then_some(iq)
+    }
+
+    /// Ingest a new sample and return polar output.
+    ///
+    /// As [`LockinNco::update()`] but returns magnitude (see
+    /// [`magnitude()`]) and phase (see [`ComplexExt::arg()`], full turn is
+    /// `1 << 32`) of the demodulated signal every `rate`-th sample.
+    pub fn update_polar(&mut self, x: i32, k: &LockinNcoConfig<T::Config>) -> Option<(u32, i32)> {
+        self.update(x, k).map(|iq| (magnitude(iq), iq.arg()))
+    }
+
+    /// Account for `n` missed input samples.
+    ///
+    /// Advances the NCO phase and the decimation index by `n` samples,
+    /// keeping the demodulation phase coherent across the gap.
+    /// The filters hold their state.
+    pub fn advance(&mut self, n: u32, k: &LockinNcoConfig<T::Config>) {
+        self.phase = self.phase.wrapping_add(k.frequency.wrapping_mul(n as i32));
+        let n = n % k.rate;
+        self.index = if self.index >= n {
+            self.index - n
+        } else {
+            self.index + k.rate - n
+        };
+    }
+
+    /// The current NCO phase (excluding the offset)
+    pub fn phase(&self) -> i32 {
+        self.phase
+    }
+
+    /// Set the NCO phase.
+    pub fn set_phase(&mut self, phase: i32) {
+        self.phase = phase;
+    }
+
+    /// The underlying lock-in filters
+    pub fn lockin(&self) -> &Lockin<T> {
+        &self.lockin
+    }
+}
+
+/// [`LockinHarmonic`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockinHarmonicConfig<C> {
+    /// Fundamental configuration
+    pub fundamental: LockinNcoConfig<C>,
+    /// Harmonic order, e.g. `3` for 3f demodulation
+    pub harmonic: i32,
+    /// Harmonic demodulation phase offset, full turn is `1 << 32`
+    pub phase: i32,
+}
+
+/// Dual-harmonic lock-in amplifier
+///
+/// Demodulates simultaneously at the fundamental and at a harmonic
+/// of the NCO frequency. Both mixers share the NCO: the harmonic
+/// phase is the (wrapping) multiple of the NCO phase.
+/// Both use the same filter configuration.
+///
+/// ```
+/// # use idsp::{cossin, LockinHarmonic, LockinHarmonicConfig, LockinNcoConfig, Lowpass};
+/// let k = LockinHarmonicConfig {
+///     fundamental: LockinNcoConfig {
+///         frequency: 1 << 26,
+///         phase: 0,
+///         rate: 1,
+///         filter: [1 << 12, -0x5a_8279],
+///     },
+///     harmonic: 3,
+///     phase: 0,
+/// };
+/// let mut l = LockinHarmonic::<Lowpass<2>>::default();
+/// let mut iq = [Default::default(); 2];
+/// for i in 0..10_000 {
+///     let p = (i as i32) << 26;
+///     let x = (cossin(p).0 >> 2) + (cossin(p.wrapping_mul(3)).0 >> 1);
+///     iq = l.update(x, &k).unwrap();
+/// }
+/// assert!((iq[0].re - (1 << 28)).abs() < 1 << 16);
+/// assert!((iq[1].re - (1 << 29)).abs() < 1 << 16);
+/// assert!(iq[0].im.abs() < 1 << 16 && iq[1].im.abs() < 1 << 16);
+/// ```
+#[derive(Copy, Clone, Default)]
+pub struct LockinHarmonic<T> {
+    fundamental: LockinNco<T>,
+    harmonic: Lockin<T>,
+}
+
+impl<T: Filter> LockinHarmonic<T> {
+    /// Ingest a new sample.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `k`: Configuration
+    ///
+    /// # Returns
+    /// The demodulated components at the fundamental and at the harmonic
+    /// every `rate`-th sample.
+    pub fn update(
+        &mut self,
+        x: i32,
+        k: &LockinHarmonicConfig<T::Config>,
+    ) -> Option<[Complex<i32>; 2]> {
+        let phase = self
+            .fundamental
+            .phase()
+            .wrapping_mul(k.harmonic)
+            .wrapping_add(k.phase);
+        let harmonic = self.harmonic.update(x, phase, &k.fundamental.filter);
+        self.fundamental
+            .update(x, &k.fundamental)
+            .map(|fundamental| [fundamental, harmonic])
+    }
+
+    /// The fundamental lock-in
+    pub fn fundamental(&self) -> &LockinNco<T> {
+        &self.fundamental
+    }
+
+    /// Set the NCO phase.
+    pub fn set_phase(&mut self, phase: i32) {
+        self.fundamental.set_phase(phase);
+    }
+}
+
+/// I/Q down-converter
+///
+/// Mixes the input with an external reference phase (e.g. from
+/// [`crate::PLL`] or [`crate::RPLL`]) and decimates by averaging
+/// (integrate and dump, a first order CIC filter).
+/// This is lighter than [`Lockin`] and [`LockinNco`] and does not
+/// need a lowpass configuration.
+///
+/// ```
+/// # use idsp::{cossin, DownConverter};
+/// let mut d = DownConverter::default();
+/// let mut iq = None;
+/// for i in 0..64 {
+///     let p = (i as i32) << 27;
+///     let x = cossin(p).0 >> 1;
+///     if let Some(y) = d.update(x, p, 32) {
+///         iq = Some(y);
+///     }
+/// }
+/// let iq = iq.unwrap();
+/// assert!((iq.re - (1 << 29)).abs() < 1 << 16);
+/// assert!(iq.im.abs() < 1 << 8);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DownConverter {
+    sum: Complex<i64>,
+    index: u32,
+}
+
+impl DownConverter {
+    /// Ingest a new sample.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `phase`: Reference phase at the sample, full turn is `1 << 32`
+    /// * `rate`: Decimation rate, `rate > 0`
+    ///
+    /// # Returns
+    /// The average complex baseband over the last `rate` samples, every
+    /// `rate`-th sample.
+    pub fn update(&mut self, x: i32, phase: i32, rate: u32) -> Option<Complex<i32>> {
+        let mix = Complex::from_angle(phase).mul_scaled(x);
+        self.sum.re += mix.re as i64;
+        self.sum.im += mix.im as i64;
+        self.index += 1;
+        (self.index >= rate).then(|| {
+            let iq = Complex {
+                re: (self.sum.re / self.index as i64) as i32,
+                im: (self.sum.im / self.index as i64) as i32,
+            };
+            *self = Self::default();
+            iq
+        })
+    }
+
+    /// Number of samples accumulated since the last output
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// Heterodyne frequency shifter
+///
+/// Shifts the frequency of a complex or real input by multiplication with
+/// the output of a numerically controlled oscillator (NCO). No filtering
+/// or decimation is performed.
+///
+/// ```
+/// # use idsp::{Complex, ComplexExt, Heterodyne};
+/// let mut h = Heterodyne::default();
+/// let f = 1 << 28;
+/// for i in 0..10i32 {
+///     let x = Complex::from_angle(i.wrapping_mul(3 * f));
+///     let y = h.update(x, -f);
+///     // Shifted from 3f to 2f
+///     assert!((y.arg().wrapping_sub(i.wrapping_mul(2 * f))).abs() < 1 << 16);
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Heterodyne {
+    phase: i32,
+}
+
+impl Heterodyne {
+    /// Shift a complex sample.
+    ///
+    /// # Args
+    /// * `x`: Input sample
+    /// * `frequency`: Frequency shift, a full turn per sample is `1 << 32`
+    pub fn update(&mut self, x: Complex<i32>, frequency: i32) -> Complex<i32> {
+        let y = x.mul_scaled(Complex::from_angle(self.phase));
+        self.phase = self.phase.wrapping_add(frequency);
+        y
+    }
+
+    /// Shift a real sample.
+    ///
+    /// The output contains both the up and the down shifted images.
+    ///
+    /// # Args
+    /// * `x`: Input sample
+    /// * `frequency`: Frequency shift, a full turn per sample is `1 << 32`
+    pub fn update_real(&mut self, x: i32, frequency: i32) -> Complex<i32> {
+        let y = Complex::from_angle(self.phase).mul_scaled(x);
+        self.phase = self.phase.wrapping_add(frequency);
+        y
+    }
+
+    /// The current NCO phase
+    pub fn phase(&self) -> i32 {
+        self.phase
+    }
+
+    /// Set the NCO phase.
+    pub fn set_phase(&mut self, phase: i32) {
+        self.phase = phase;
+    }
+}
+
+/// CIC lock-in amplifier
+///
+/// A decimating lock-in with an NCO where the mixer products are fed
+/// directly into a pair of [`CicDecimator`]s. This avoids the full rate
+/// lowpass filters of [`LockinNco`] and is the fastest lock-in
+/// for high decimation rates.
+/// The output is normalized to unity DC gain of the CIC filters, i.e.
+/// it is half the input amplitude.
+///
+/// ```
+/// # use idsp::{cossin, LockinCic};
+/// let mut l = LockinCic::<3>::new(16);
+/// let f = 1 << 28;
+/// let mut iq = None;
+/// for i in 0..64 {
+///     let x = cossin((i as i32).wrapping_mul(f)).0 >> 1;
+///     iq = l.update(x, f, 0).or(iq);
+/// }
+/// let iq = iq.unwrap();
+/// assert!((iq.re - (1 << 29)).abs() < 1 << 16);
+/// assert!(iq.im.abs() < 1 << 16);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LockinCic<const N: usize> {
+    cic: [CicDecimator<N>; 2],
+    phase: i32,
+}
+
+impl<const N: usize> LockinCic<N> {
+    /// Create a new lock-in
+    ///
+    /// # Args
+    /// * `rate`: Decimation rate, see [`CicDecimator`] for limits
+    pub fn new(rate: u32) -> Self {
+        Self {
+            cic: [CicDecimator::new(rate); 2],
+            phase: 0,
+        }
+    }
+
+    /// Ingest a new sample.
+    ///
+    /// # Args
+    /// * `x`: New input sample
+    /// * `frequency`: NCO frequency, full turn per sample is `1 << 32`
+    /// * `phase`: Demodulation phase offset, full turn is `1 << 32`
+    ///
+    /// # Returns
+    /// The demodulated in-phase and quadrature components every `rate`-th
+    /// sample.
+    pub fn update(&mut self, x: i32, frequency: i32, phase: i32) -> Option<Complex<i32>> {
+        let mix = Complex::from_angle(self.phase.wrapping_add(phase)).mul_scaled(x);
+        self.phase = self.phase.wrapping_add(frequency);
+        let re = self.cic[0].update(mix.re);
+        let im = self.cic[1].update(mix.im);
+        let gain = self.cic[0].gain() as i64;
+        re.zip(im)
+            .map(|(re, im)| Complex::new((re / gain) as i32, (im / gain) as i32))
+    }
+
+    /// Ingest a new sample and return polar output.
+    ///
+    /// As [`LockinCic::update()`] but returns magnitude (see
+    /// [`magnitude()`]) and phase (see [`ComplexExt::arg()`], full turn is
+    /// `1 << 32`) of the demodulated signal every `rate`-th sample.
+    ///
+    /// ```
+    /// # use idsp::{cossin, LockinCic};
+    /// let mut l = LockinCic::<2>::new(16);
+    /// let f = 1 << 28;
+    /// let mut rp = None;
+    /// for i in 0..64 {
+    ///     let x = cossin((i as i32).wrapping_mul(f).wrapping_add(1 << 29)).0 >> 1;
+    ///     rp = l.update_polar(x, f, 0).or(rp);
+    /// }
+    /// let (r, p) = rp.unwrap();
+    /// assert!((r as i32 - (1 << 29)).abs() < 1 << 16);
+    /// assert!((p + (1 << 29)).abs() < 1 << 16);
+    /// ```
+    pub fn update_polar(&mut self, x: i32, frequency: i32, phase: i32) -> Option<(u32, i32)> {
+        self.update(x, frequency, phase)
+            .map(|iq| (magnitude(iq), iq.arg()))
+    }
+
+    /// The decimation rate
+    pub fn rate(&self) -> u32 {
+        self.cic[0].rate()
+    }
+
+    /// The current NCO phase (excluding the offset)
+    pub fn phase(&self) -> i32 {
+        self.phase
+    }
+
+    /// Set the NCO phase.
+    pub fn set_phase(&mut self, phase: i32) {
+        self.phase = phase;
+    }
 }
@@ -32,6 +32,10 @@ impl<const N: usize> Filter for Lowpass<N> {
     /// `1 << 16 <= k <= q*(1 << 31)`.
     type Config = [i32; N];
     fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        overflow_check!(
+            x.checked_sub(self.get()).is_some(),
+            "Lowpass error saturates"
+        );
         let mut d = x.saturating_sub(self.get()) as i64 * k[0] as i64;
         let y;
         if N == 1 {
@@ -74,3 +78,266 @@ impl<const N: usize> Default for Lowpass<N> {
 pub type Lowpass1 = Lowpass<1>;
 /// Second order lowpass
 pub type Lowpass2 = Lowpass<2>;
+
+/// Gain slewing for [`Lowpass`]
+///
+/// Moves the gain configuration `k` linearly towards a target over a given
+/// number of updates. Bandwidth changes (e.g. after lock acquisition)
+/// then do not inject steps into the filter output.
+///
+/// The current gain is used as the configuration for one or more
+/// [`Lowpass`] filters (e.g. both quadratures of a [`crate::Lockin`]).
+///
+/// ```
+/// # use idsp::{Filter, Lowpass1, LowpassSlew};
+/// let mut lp = Lowpass1::default();
+/// let mut k = LowpassSlew::new([1 << 20]);
+/// k.set_target([1 << 24], 16);
+/// for _ in 0..16 {
+///     lp.update(1 << 20, k.update());
+/// }
+/// assert_eq!(k.get(), &[1 << 24]);
+/// assert_eq!(k.remaining(), 0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LowpassSlew<const N: usize> {
+    k: [i32; N],
+    target: [i32; N],
+    step: [i32; N],
+    remaining: u32,
+}
+
+impl<const N: usize> Default for LowpassSlew<N> {
+    fn default() -> Self {
+        Self::new([0; N])
+    }
+}
+
+impl<const N: usize> LowpassSlew<N> {
+    /// Create a new gain slew at the given gain.
+    pub fn new(k: [i32; N]) -> Self {
+        Self {
+            k,
+            target: k,
+            step: [0; N],
+            remaining: 0,
+        }
+    }
+
+    /// Set a new target gain.
+    ///
+    /// # Args
+    /// * `target`: Target gain. See [`Lowpass`] for the configuration.
+    /// * `n`: Number of updates to reach the target. The target is applied
+    ///   immediately for `n = 0`.
+    pub fn set_target(&mut self, target: [i32; N], n: u32) {
+        self.target = target;
+        self.remaining = n;
+        if n == 0 {
+            self.k = target;
+        } else {
+            for ((step, k), t) in self.step.iter_mut().zip(self.k.iter()).zip(target.iter()) {
+                *step = ((*t as i64 - *k as i64) / n as i64) as i32;
+            }
+        }
+    }
+
+    /// Advance the gain by one step towards the target and return it.
+    pub fn update(&mut self) -> &[i32; N] {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            if self.remaining == 0 {
+                // Avoid accumulated rounding errors
+                self.k = self.target;
+            } else {
+                for (k, step) in self.k.iter_mut().zip(self.step.iter()) {
+                    *k += *step;
+                }
+            }
+        }
+        &self.k
+    }
+
+    /// The current gain
+    pub fn get(&self) -> &[i32; N] {
+        &self.k
+    }
+
+    /// The target gain
+    pub fn target(&self) -> &[i32; N] {
+        &self.target
+    }
+
+    /// Number of updates remaining until the target is reached
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+}
+
+/// Settling detector
+///
+/// Reports whether a signal has converged to within a band of given
+/// width around a reference for a given number of updates.
+///
+/// Fed with the input and output of a [`Lowpass`] (of any order), this
+/// determines when the output has settled to the input mean, e.g. to
+/// sequence lock acquisition state machines.
+///
+/// ```
+/// # use idsp::{Filter, Lowpass2, Settle};
+/// let mut lp = Lowpass2::default();
+/// let mut s = Settle::default();
+/// let k = [1 << 16, -0x16a_09e6]; // k = 1 << 24, q = 1/sqrt(2)
+/// let x = 1 << 20;
+/// let n = (0..1 << 12)
+///     .position(|_| s.settled(x, lp.update(x, &k), 1 << 9, 1 << 8))
+///     .unwrap();
+/// assert!(n > 1 << 8);
+/// assert!((lp.get() - x).abs() <= 1 << 9);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Settle {
+    count: u32,
+}
+
+impl Settle {
+    /// Ingest a new sample and return whether the signal has settled.
+    ///
+    /// # Args
+    /// * `x`: Reference, e.g. the lowpass input
+    /// * `y`: New sample, e.g. the lowpass output
+    /// * `tolerance`: Half-width of the band around the reference that the
+    ///   signal needs to stay within.
+    /// * `duration`: Number of samples the signal needs to stay in the band.
+    ///
+    /// # Returns
+    /// `true` if the last `duration` samples have been within the band.
+    pub fn settled(&mut self, x: i32, y: i32, tolerance: u32, duration: u32) -> bool {
+        if y.abs_diff(x) > tolerance {
+            self.count = 0;
+        } else {
+            self.count = self.count.saturating_add(1);
+        }
+        self.count >= duration
+    }
+
+    /// Number of successive samples within the band
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Reset the detector
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+}
+
+/// Exponential moving average
+///
+/// `y0 = y1 + alpha*(x0 - y1)`
+///
+/// The weight `alpha` is a full resolution unsigned fixed point multiplier in
+/// units of `1 << 32`. This allows matching time constants precisely, e.g.
+/// to an analog reference. For a time constant `tau` in units of the sample
+/// period, `alpha = (1 - exp(-1/tau))*(1 << 32)`.
+///
+/// In contrast to [`Lowpass1`] there is no zero at Nyquist.
+///
+/// The filter state is wide and the output saturates to the `i32` range.
+///
+/// ```
+/// # use idsp::{Ewma, Filter};
+/// let mut e = Ewma::default();
+/// let alpha = 1 << 30; // 0.25
+/// let y: Vec<_> = (0..4).map(|_| e.update(1 << 20, &alpha)).collect();
+/// assert_eq!(y, [0x40000, 0x70000, 0x94000, 0xaf000]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ewma(i64);
+
+impl Filter for Ewma {
+    /// The weight `alpha` in units of `1 << 32`
+    type Config = u32;
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        overflow_check!(x.checked_sub(self.get()).is_some(), "Ewma error saturates");
+        self.0 += x.saturating_sub(self.get()) as i64 * *k as i64;
+        self.get()
+    }
+
+    fn get(&self) -> i32 {
+        (self.0 >> 32) as i32
+    }
+
+    fn set(&mut self, x: i32) {
+        self.0 = (x as i64) << 32;
+    }
+}
+
+/// Leaky integrator
+///
+/// `y0 = y1 + x0 - (y1 >> k)`
+///
+/// An integrator with a power-of-two leak: the DC gain is `1 << k` and the
+/// time constant is `1 << k` samples. The state is wide (`i64`) and
+/// does not grow without bound. This is useful for energy accumulation
+/// (with squared inputs) and slow baseline estimation.
+///
+/// [`Filter::get()`] returns the state saturated to the `i32` range,
+/// [`LeakyIntegrator::sum()`] the full state.
+///
+/// ```
+/// # use idsp::{Filter, LeakyIntegrator};
+/// let mut l = LeakyIntegrator::default();
+/// let y: Vec<_> = (0..4).map(|_| l.update(16, &2)).collect();
+/// assert_eq!(y, [16, 28, 37, 44]);
+/// for _ in 0..100 {
+///     l.update(16, &2);
+/// }
+/// assert_eq!(l.sum(), 16 << 2);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeakyIntegrator(i64);
+
+impl LeakyIntegrator {
+    /// The integrator state
+    pub fn sum(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Filter for LeakyIntegrator {
+    /// Leak shift `k`, `k <= 32`
+    type Config = u32;
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        debug_assert!(*k <= 32);
+        self.0 += x as i64 - (self.0 >> *k);
+        self.get()
+    }
+
+    fn get(&self) -> i32 {
+        self.0.clamp(i32::MIN as _, i32::MAX as _) as _
+    }
+
+    fn set(&mut self, x: i32) {
+        self.0 = x as _;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn settle_drift() {
+        let mut s = Settle::default();
+        // Slowly drifting output away from the reference,
+        // stationary within the band for many samples
+        for i in 0..1 << 12 {
+            assert!(!s.settled(0, (1 << 9) + (i >> 4), 1 << 8, 1 << 4));
+        }
+        for _ in 0..1 << 4 {
+            s.settled(0, 1 << 7, 1 << 8, 1 << 4);
+        }
+        assert!(s.settled(0, -(1 << 7), 1 << 8, 1 << 4));
+    }
+}
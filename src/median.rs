@@ -0,0 +1,286 @@
+use crate::Filter;
+
+/// Rank order filter
+///
+/// Outputs the sample of a given rank (order statistic) within the
+/// last `N` input samples: rank `0` is the minimum, rank `N - 1` the maximum,
+/// rank `N/2` the median (see [`Median`]).
+/// Low percentiles of a window track a baseline under asymmetric
+/// (e.g. positive spike) noise.
+///
+/// The samples are kept both in arrival order and sorted.
+/// An update removes the oldest sample from and inserts the new sample
+/// into the sorted window using binary search: `O(log N)` comparisons
+/// and `O(N)` copies.
+///
+/// ```
+/// # use idsp::RankOrder;
+/// let mut r = RankOrder::<i32, 5>::default();
+/// for x in [3, 100, 1, 4, 2] {
+///     r.push(x);
+/// }
+/// assert_eq!(r.rank(0), 1);
+/// assert_eq!(r.rank(4), 100);
+/// assert_eq!(r.percentile(25), 2);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RankOrder<T, const N: usize> {
+    /// Samples in arrival order (ring buffer)
+    x: [T; N],
+    /// Samples sorted ascending
+    sorted: [T; N],
+    /// Index of the oldest sample in `x`
+    idx: usize,
+    /// Rank of the last [`Filter`] output
+    k: usize,
+}
+
+impl<T: Copy + Default, const N: usize> Default for RankOrder<T, N> {
+    fn default() -> Self {
+        Self {
+            x: [T::default(); N],
+            sorted: [T::default(); N],
+            idx: 0,
+            k: N / 2,
+        }
+    }
+}
+
+impl<T: Copy + PartialOrd, const N: usize> RankOrder<T, N> {
+    /// Ingest a new sample, replacing the oldest.
+    pub fn push(&mut self, x: T) {
+        let old = core::mem::replace(&mut self.x[self.idx], x);
+        self.idx = if self.idx + 1 == N { 0 } else { self.idx + 1 };
+        // Position of the oldest sample
+        let i = self.sorted.partition_point(|s| *s < old);
+        // Insertion position for the new sample, not counting the oldest
+        let j = self.sorted.partition_point(|s| *s < x);
+        if j > i {
+            self.sorted.copy_within(i + 1..j, i);
+            self.sorted[j - 1] = x;
+        } else {
+            self.sorted.copy_within(j..i, j + 1);
+            self.sorted[j] = x;
+        }
+    }
+
+    /// The sample of a given rank
+    ///
+    /// # Args
+    /// * `k`: Rank, `0 <= k < N`
+    pub fn rank(&self, k: usize) -> T {
+        self.sorted[k]
+    }
+
+    /// The sample at a given percentile
+    ///
+    /// # Args
+    /// * `p`: Percentile, `0 <= p <= 100`. The rank is rounded to nearest.
+    pub fn percentile(&self, p: usize) -> T {
+        debug_assert!(p <= 100);
+        self.rank((p * (N - 1) + 50) / 100)
+    }
+
+    /// The window samples sorted ascending
+    pub fn sorted(&self) -> &[T; N] {
+        &self.sorted
+    }
+
+    /// Fill the window with the given value.
+    pub fn set(&mut self, x: T) {
+        self.x = [x; N];
+        self.sorted = [x; N];
+    }
+}
+
+/// The filter output is the newest sample of the configured rank.
+impl<const N: usize> Filter for RankOrder<i32, N> {
+    /// Rank `0 <= k < N`
+    type Config = usize;
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        self.push(x);
+        self.k = *k;
+        self.rank(*k)
+    }
+    /// The sample of the rank last used in `update()`, initially the median
+    fn get(&self) -> i32 {
+        self.rank(self.k)
+    }
+    fn set(&mut self, x: i32) {
+        RankOrder::set(self, x)
+    }
+}
+
+/// Median filter
+///
+/// Outputs the median of the last `N` input samples.
+/// Spikes and glitches shorter than `N/2` samples are rejected while edges
+/// are preserved.
+///
+/// For even `N` the upper median is returned.
+/// See also [`RankOrder`].
+///
+/// ```
+/// # use idsp::Median;
+/// let mut m = Median::<i32, 3>::default();
+/// let y: Vec<_> = [1, 1, 9, 1, 2, 3].iter().map(|x| m.update(*x)).collect();
+/// assert_eq!(y, [0, 1, 1, 1, 2, 2]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Median<T, const N: usize>(RankOrder<T, N>);
+
+impl<T: Copy + Default, const N: usize> Default for Median<T, N> {
+    fn default() -> Self {
+        Self(RankOrder::default())
+    }
+}
+
+impl<T: Copy + PartialOrd, const N: usize> Median<T, N> {
+    /// Ingest a new sample and return the new median.
+    pub fn update(&mut self, x: T) -> T {
+        self.0.push(x);
+        self.get()
+    }
+
+    /// The current median
+    pub fn get(&self) -> T {
+        self.0.rank(N / 2)
+    }
+
+    /// Fill the window with the given value.
+    pub fn set(&mut self, x: T) {
+        self.0.set(x)
+    }
+
+    /// The underlying rank order filter
+    pub fn inner(&self) -> &RankOrder<T, N> {
+        &self.0
+    }
+}
+
+impl<const N: usize> Filter for Median<i32, N> {
+    type Config = ();
+    fn update(&mut self, x: i32, _k: &Self::Config) -> i32 {
+        Median::update(self, x)
+    }
+    fn get(&self) -> i32 {
+        Median::get(self)
+    }
+    fn set(&mut self, x: i32) {
+        Median::set(self, x)
+    }
+}
+
+/// Hampel outlier rejection filter
+///
+/// Compares the center sample of the last `N` input samples
+/// (delayed by `N/2`) to the window median. Samples deviating from the median
+/// by more than the threshold times the median absolute deviation (MAD)
+/// are replaced by the median. All other samples pass unchanged.
+///
+/// For normally distributed data the standard deviation is about `1.4826`
+/// times the MAD: a threshold of `3σ` is about `4.45` MAD.
+///
+/// The MAD is computed by selection in `O(N)` per sample.
+///
+/// ```
+/// # use idsp::{Filter, Hampel};
+/// let mut h = Hampel::<5>::default();
+/// let x = [1, 2, 1, 3, 1000, 2, 1, 2, 0, 0];
+/// let y: Vec<_> = x.iter().map(|x| h.update(*x, &(3 << 16))).collect();
+/// assert_eq!(y[4..], [1, 3, 2, 2, 1, 2]);
+/// assert_eq!(h.outliers(), 1);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Hampel<const N: usize> {
+    window: RankOrder<i32, N>,
+    y: i32,
+    outliers: u32,
+}
+
+impl<const N: usize> Default for Hampel<N> {
+    fn default() -> Self {
+        Self {
+            window: RankOrder::default(),
+            y: 0,
+            outliers: 0,
+        }
+    }
+}
+
+impl<const N: usize> Hampel<N> {
+    /// The median absolute deviation of the current window
+    pub fn mad(&self) -> u32 {
+        let m = self.window.rank(N / 2);
+        let mut dev = self.window.sorted.map(|x| x.abs_diff(m));
+        *dev.select_nth_unstable(N / 2).1
+    }
+
+    /// Number of samples replaced
+    pub fn outliers(&self) -> u32 {
+        self.outliers
+    }
+
+    /// Reset the outlier counter.
+    pub fn clear(&mut self) {
+        self.outliers = 0;
+    }
+}
+
+impl<const N: usize> Filter for Hampel<N> {
+    /// Threshold in units of the median absolute deviation,
+    /// `1 << 16` is one MAD
+    type Config = u32;
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        self.window.push(x);
+        let w = &self.window;
+        // The oldest sample is at `idx`
+        let c = w.x[(w.idx + N / 2) % N];
+        let m = w.rank(N / 2);
+        self.y = if c.abs_diff(m) as u64 > (self.mad() as u64 * *k as u64) >> 16 {
+            self.outliers = self.outliers.wrapping_add(1);
+            m
+        } else {
+            c
+        };
+        self.y
+    }
+    fn get(&self) -> i32 {
+        self.y
+    }
+    fn set(&mut self, x: i32) {
+        self.window.set(x);
+        self.y = x;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{prelude::*, rngs::StdRng};
+
+    #[test]
+    fn sorted() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut m = Median::<i32, 7>::default();
+        let mut x = vec![0; 7];
+        for _ in 0..1000 {
+            let xi = rng.gen_range(-5..5);
+            x.push(xi);
+            let y = m.update(xi);
+            let mut w = x[x.len() - 7..].to_vec();
+            w.sort();
+            assert_eq!(w, m.inner().sorted());
+            assert_eq!(y, w[3]);
+        }
+    }
+
+    #[test]
+    fn filter_get() {
+        let mut r = RankOrder::<i32, 5>::default();
+        for x in [3, 5, 1, 4, 2] {
+            assert_eq!(Filter::update(&mut r, x, &0), Filter::get(&r));
+        }
+        assert_eq!(Filter::get(&r), 1);
+    }
+}
@@ -0,0 +1,178 @@
+/// Monotonic ring buffer deque of (sample index, value) pairs
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Deque<T, const N: usize> {
+    buf: [(usize, T); N],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> Deque<T, N> {
+    fn new(x: T) -> Self {
+        Self {
+            buf: [(0, x); N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Expire entries older than `n - N`, drop entries from the back
+    /// that are dominated by `x`, and append `x`.
+    fn push(&mut self, n: usize, x: T, dominates: impl Fn(&T, &T) -> bool) {
+        if self.len > 0 && n.wrapping_sub(self.buf[self.head].0) >= N {
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+        }
+        while self.len > 0 && dominates(&x, &self.buf[(self.head + self.len - 1) % N].1) {
+            self.len -= 1;
+        }
+        self.buf[(self.head + self.len) % N] = (n, x);
+        self.len += 1;
+    }
+
+    fn front(&self) -> T {
+        self.buf[self.head].1
+    }
+}
+
+/// Windowed minimum and maximum tracker
+///
+/// Tracks the minimum and maximum of the last `N` samples, e.g. for
+/// envelope detection and out-of-range monitoring.
+///
+/// Uses a pair of monotonic deques. The update cost is amortized `O(1)`
+/// and worst case `O(N)`.
+///
+/// ```
+/// # use idsp::MinMax;
+/// let mut m = MinMax::<i32, 3>::new(0);
+/// let y: Vec<_> = [3, 1, 4, 1, 5, 9, 2, 6].iter().map(|x| m.update(*x)).collect();
+/// assert_eq!(y, [(3, 3), (1, 3), (1, 4), (1, 4), (1, 5), (1, 9), (2, 9), (2, 9)]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MinMax<T, const N: usize> {
+    n: usize,
+    min: Deque<T, N>,
+    max: Deque<T, N>,
+}
+
+impl<T: Copy + PartialOrd + Default, const N: usize> Default for MinMax<T, N> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Copy + PartialOrd, const N: usize> MinMax<T, N> {
+    /// Create a new tracker
+    ///
+    /// # Args
+    /// * `x`: Value of the minimum and maximum until the first update
+    pub fn new(x: T) -> Self {
+        Self {
+            n: 0,
+            min: Deque::new(x),
+            max: Deque::new(x),
+        }
+    }
+
+    /// Ingest a new sample
+    ///
+    /// # Returns
+    /// The minimum and maximum over the window
+    pub fn update(&mut self, x: T) -> (T, T) {
+        self.n = self.n.wrapping_add(1);
+        self.min.push(self.n, x, |x, y| x <= y);
+        self.max.push(self.n, x, |x, y| x >= y);
+        (self.min(), self.max())
+    }
+
+    /// The minimum over the window
+    pub fn min(&self) -> T {
+        self.min.front()
+    }
+
+    /// The maximum over the window
+    pub fn max(&self) -> T {
+        self.max.front()
+    }
+}
+
+/// Min/mean/max decimator
+///
+/// Outputs the minimum, mean, and maximum of each block of `n` input
+/// samples. Use to stream high rate signals at low rate without aliasing
+/// away peaks, e.g. for plotting.
+///
+/// ```
+/// # use idsp::MinMeanMax;
+/// let mut m = MinMeanMax::default();
+/// let y: Vec<_> = [3, 1, 4, 1, 5, 9, 2, 6]
+///     .iter()
+///     .filter_map(|x| m.update(*x, 4))
+///     .collect();
+/// assert_eq!(y, [(1, 2, 4), (2, 5, 9)]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MinMeanMax {
+    min: i32,
+    max: i32,
+    sum: i64,
+    count: u32,
+}
+
+impl Default for MinMeanMax {
+    fn default() -> Self {
+        Self {
+            min: i32::MAX,
+            max: i32::MIN,
+            sum: 0,
+            count: 0,
+        }
+    }
+}
+
+impl MinMeanMax {
+    /// Ingest a new sample
+    ///
+    /// # Args
+    /// * `x`: Input sample
+    /// * `n`: Block length, `n > 0`
+    ///
+    /// # Returns
+    /// The minimum, mean (rounded down), and maximum of the block every
+    /// `n`-th sample
+    pub fn update(&mut self, x: i32, n: u32) -> Option<(i32, i32, i32)> {
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+        self.sum += x as i64;
+        self.count += 1;
+        (self.count >= n).then(|| {
+            let y = (
+                self.min,
+                self.sum.div_euclid(self.count as i64) as i32,
+                self.max,
+            );
+            *self = Self::default();
+            y
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{prelude::*, rngs::StdRng};
+
+    #[test]
+    fn brute() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut m = MinMax::<i32, 5>::default();
+        let mut x = vec![];
+        for _ in 0..1000 {
+            let xi = rng.gen_range(-10..10);
+            x.push(xi);
+            let w = &x[x.len().saturating_sub(5)..];
+            let want = (*w.iter().min().unwrap(), *w.iter().max().unwrap());
+            assert_eq!(m.update(xi), want);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use num_traits::AsPrimitive;
+
+use crate::{isqrt, Coefficient, Filter};
+
+/// Moving average (boxcar) filter
+///
+/// Averages the last `N` input samples.
+/// The running sum is kept in the wide accumulator type [`Coefficient::ACCU`]
+/// and updated in O(1) per sample. For integer types this is exact and does not
+/// drift.
+///
+/// The transfer function has zeros at multiples of `1/N` of the sample rate.
+/// E.g. with `N = fs/50` the filter notches out 50 Hz line frequency and
+/// all its harmonics.
+///
+/// ```
+/// # use idsp::MovingAverage;
+/// let mut m = MovingAverage::<i32, 4>::default();
+/// let y: Vec<_> = [4, 8, 0, 4, 4, 4].iter().map(|x| m.update(*x)).collect();
+/// assert_eq!(y, [1, 3, 3, 4, 4, 3]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MovingAverage<T: Coefficient, const N: usize> {
+    x: [T; N],
+    idx: usize,
+    sum: T::ACCU,
+}
+
+impl<T: Coefficient, const N: usize> Default for MovingAverage<T, N> {
+    fn default() -> Self {
+        Self {
+            x: [T::ZERO; N],
+            idx: 0,
+            sum: T::ZERO.as_(),
+        }
+    }
+}
+
+impl<T: Coefficient, const N: usize> MovingAverage<T, N>
+where
+    usize: AsPrimitive<T::ACCU>,
+{
+    /// Ingest a new sample and return the new average.
+    ///
+    /// For integer types the average is truncated towards zero.
+    pub fn update(&mut self, x: T) -> T {
+        self.sum = self.sum - self.x[self.idx].as_() + x.as_();
+        self.x[self.idx] = x;
+        self.idx = if self.idx + 1 == N { 0 } else { self.idx + 1 };
+        self.get()
+    }
+
+    /// The current average
+    pub fn get(&self) -> T {
+        (self.sum / N.as_()).as_()
+    }
+
+    /// The current sum of the last `N` samples
+    pub fn sum(&self) -> T::ACCU {
+        self.sum
+    }
+
+    /// Fill the window with the given value.
+    pub fn set(&mut self, x: T) {
+        self.x = [x; N];
+        self.sum = x.as_() * N.as_();
+    }
+}
+
+impl<const N: usize> Filter for MovingAverage<i32, N> {
+    type Config = ();
+    fn update(&mut self, x: i32, _k: &Self::Config) -> i32 {
+        MovingAverage::update(self, x)
+    }
+    fn get(&self) -> i32 {
+        MovingAverage::get(self)
+    }
+    fn set(&mut self, x: i32) {
+        MovingAverage::set(self, x)
+    }
+}
+
+/// Moving RMS estimator
+///
+/// Squares the input, averages the last `N` squares with a
+/// [`MovingAverage`] and takes the integer square root.
+/// The output is in units of the input: a full scale square wave of
+/// amplitude `A` yields `A`, a sine of amplitude `A` yields `A/sqrt(2)`.
+/// The mean square is exact (truncated towards zero) and the
+/// square root is rounded down.
+///
+/// ```
+/// # use idsp::MovingRms;
+/// let mut r = MovingRms::<4>::default();
+/// for x in [3, -3, 3, -3] {
+///     r.update(x);
+/// }
+/// assert_eq!(r.get(), 3);
+/// assert_eq!(r.update(0), 2); // sqrt(27/4)
+/// assert_eq!(r.mean_square(), 6);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct MovingRms<const N: usize>(MovingAverage<i64, N>);
+
+impl<const N: usize> MovingRms<N> {
+    /// Ingest a new sample and return the new RMS value.
+    pub fn update(&mut self, x: i32) -> u32 {
+        self.0.update(x as i64 * x as i64);
+        self.get()
+    }
+
+    /// The current RMS value
+    pub fn get(&self) -> u32 {
+        isqrt(self.mean_square()) as _
+    }
+
+    /// The current mean square value
+    pub fn mean_square(&self) -> u64 {
+        self.0.get() as _
+    }
+
+    /// Fill the window with the given RMS value.
+    pub fn set(&mut self, x: u32) {
+        self.0.set(x as i64 * x as i64);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn exact() {
+        let mut m = MovingAverage::<i32, 7>::default();
+        m.set(i32::MAX);
+        assert_eq!(m.get(), i32::MAX);
+        for i in 0..100 {
+            m.update(if i & 1 == 0 { i32::MIN } else { i32::MAX });
+        }
+        for _ in 0..7 {
+            m.update(-3);
+        }
+        assert_eq!(m.sum(), -21);
+        assert_eq!(m.get(), -3);
+    }
+
+    #[test]
+    fn float() {
+        let mut m = MovingAverage::<f32, 2>::default();
+        m.update(1.0);
+        assert_eq!(m.update(2.0), 1.5);
+    }
+
+    #[test]
+    fn rms_full_scale() {
+        let mut r = MovingRms::<2>::default();
+        r.update(i32::MIN);
+        assert_eq!(r.update(i32::MIN), 1 << 31);
+        assert_eq!(r.update(i32::MAX), (1 << 31) - 1);
+    }
+}
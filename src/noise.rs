@@ -0,0 +1,168 @@
+/// Xoshiro128++ pseudo-random number generator
+///
+/// A small, fast, `no_std` PRNG with 128 bits of state and period
+/// `2^128 - 1` producing uniformly distributed `i32` samples, e.g. for
+/// dither and excitation. Not cryptographically secure.
+///
+/// ```
+/// # use idsp::Xoshiro128;
+/// let mut r = Xoshiro128::from_state([1, 2, 3, 4]);
+/// assert_eq!(r.next_u32(), 641);
+/// let mut r = Xoshiro128::new(42);
+/// let mean = (0..1 << 16).map(|_| r.update() as i64).sum::<i64>() >> 16;
+/// assert!(mean.abs() < 1 << 25);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Xoshiro128 {
+    s: [u32; 4],
+}
+
+impl Default for Xoshiro128 {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Xoshiro128 {
+    /// Create a new generator from a seed
+    ///
+    /// The state is initialized from the seed using SplitMix64.
+    pub fn new(mut seed: u64) -> Self {
+        let mut next = || {
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        let (a, b) = (next(), next());
+        Self::from_state([a as u32, (a >> 32) as u32, b as u32, (b >> 32) as u32])
+    }
+
+    /// Create a new generator from a raw state
+    ///
+    /// The state must not be all zero.
+    pub fn from_state(s: [u32; 4]) -> Self {
+        debug_assert!(s != [0; 4]);
+        Self { s }
+    }
+
+    /// Generate the next uniformly distributed `u32`.
+    pub fn next_u32(&mut self) -> u32 {
+        let s = &mut self.s;
+        let y = s[0].wrapping_add(s[3]).rotate_left(7).wrapping_add(s[0]);
+        let t = s[1] << 9;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(11);
+        y
+    }
+
+    /// Generate the next uniformly distributed full scale `i32` sample.
+    pub fn update(&mut self) -> i32 {
+        self.next_u32() as i32
+    }
+}
+
+impl Iterator for Xoshiro128 {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        Some(self.update())
+    }
+}
+
+/// Approximately Gaussian noise generator
+///
+/// The sum of four uniformly distributed samples from [`Xoshiro128`]
+/// (Irwin-Hall distribution), scaled to a given RMS value.
+/// The distribution is bounded to about `±3.46` times the RMS value and
+/// the kurtosis deficit is small (excess kurtosis `-0.3`).
+///
+/// ```
+/// # use idsp::GaussianNoise;
+/// let mut g = GaussianNoise::new(1);
+/// let rms = 1 << 20;
+/// let n = 1 << 16;
+/// let (mut s, mut s2) = (0, 0);
+/// for _ in 0..n {
+///     let y = g.update(rms) as i64;
+///     s += y;
+///     s2 += y * y;
+/// }
+/// assert!((s / n).abs() < 1 << 13);
+/// assert!((((s2 / n) as f64).sqrt() / rms as f64 - 1.0).abs() < 1e-2);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GaussianNoise {
+    rng: Xoshiro128,
+}
+
+impl GaussianNoise {
+    /// Create a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Xoshiro128::new(seed),
+        }
+    }
+
+    /// Generate the next sample.
+    ///
+    /// # Args
+    /// * `rms`: RMS value (standard deviation) of the output
+    pub fn update(&mut self, rms: i32) -> i32 {
+        // sqrt(3) in Q30
+        const SQRT3: i64 = 0x6ed9_eba1;
+        let s: i64 = (0..4).map(|_| (self.rng.update() >> 2) as i64).sum();
+        // The standard deviation of s is (1 << 30)/sqrt(3)
+        let y = (((s * rms as i64) >> 30) * SQRT3) >> 30;
+        y.clamp(i32::MIN as _, i32::MAX as _) as _
+    }
+}
+
+/// Triangular probability density function (TPDF) dither
+///
+/// The difference of two independent uniform draws. The output is
+/// triangularly distributed with zero mean, a width of two quantizer LSB
+/// (`-(1 << shift) < y < 1 << shift`), and a standard deviation of `1/sqrt(6)`
+/// of the quantizer LSB.
+/// Adding it before a quantizer that discards `shift` bits (e.g. [`crate::DeltaSigma`])
+/// makes the mean and the variance of the quantization error
+/// independent of the signal.
+///
+/// ```
+/// # use idsp::Tpdf;
+/// let mut t = Tpdf::new(1);
+/// let shift = 16;
+/// let y: Vec<_> = (0..1 << 16).map(|_| t.update(shift) as i64).collect();
+/// assert!(y.iter().all(|y| y.abs() < 1 << shift));
+/// let mean = y.iter().sum::<i64>() / y.len() as i64;
+/// assert!(mean.abs() < 1 << 9);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tpdf {
+    rng: Xoshiro128,
+}
+
+impl Tpdf {
+    /// Create a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Xoshiro128::new(seed),
+        }
+    }
+
+    /// Generate the next dither sample.
+    ///
+    /// # Args
+    /// * `shift`: Number of bits discarded by the quantizer (the
+    ///   quantizer LSB is `1 << shift`), `shift < 32`
+    pub fn update(&mut self, shift: u32) -> i32 {
+        let a = (self.rng.next_u32() as u64 >> (32 - shift)) as i32;
+        let b = (self.rng.next_u32() as u64 >> (32 - shift)) as i32;
+        a - b
+    }
+}
@@ -39,6 +39,34 @@ pub trait Coefficient: 'static + Copy + Num + AsPrimitive<Self::ACCU> {
     // TODO: range check and Result
 }
 
+/// Fully saturating and rounding multiply-accumulate for [`Coefficient`]s
+pub trait SaturatingMacc: Coefficient {
+    /// Saturating multiply-accumulate
+    ///
+    /// Like [`Coefficient::macc()`] but saturates correctly for any
+    /// accumulator value and any limits (no guard bit restrictions)
+    /// and without error feedback. The result is rounded down.
+    ///
+    /// ```
+    /// # use idsp::SaturatingMacc;
+    /// let s = 7i64 << 40; // overflows the guard bits
+    /// assert_eq!(0i32.macc_sat(s, -1000, 1000), 1000);
+    /// assert_eq!(3i32.macc_sat(-(5 << 29), i32::MIN, i32::MAX), 0);
+    /// ```
+    fn macc_sat(self, s: Self::ACCU, min: Self, max: Self) -> Self;
+
+    /// Rounding saturating multiply-accumulate
+    ///
+    /// Like [`SaturatingMacc::macc_sat()`] but rounds to nearest (half up).
+    ///
+    /// ```
+    /// # use idsp::SaturatingMacc;
+    /// assert_eq!(3i32.macc_round(-(5 << 29), i32::MIN, i32::MAX), 1);
+    /// assert_eq!(0i32.macc_round(-(5 << 29), i32::MIN, i32::MAX), -2);
+    /// ```
+    fn macc_round(self, s: Self::ACCU, min: Self, max: Self) -> Self;
+}
+
 macro_rules! impl_float {
     ($T:ty) => {
         impl Coefficient for $T {
@@ -75,6 +103,18 @@ macro_rules! impl_float {
                 value.as_()
             }
         }
+
+        impl SaturatingMacc for $T {
+            #[inline]
+            fn macc_sat(self, s: Self::ACCU, min: Self, max: Self) -> Self {
+                (self + s).clip(min, max)
+            }
+
+            #[inline]
+            fn macc_round(self, s: Self::ACCU, min: Self, max: Self) -> Self {
+                (self + s).clip(min, max)
+            }
+        }
     };
 }
 impl_float!(f32);
@@ -97,6 +137,10 @@ macro_rules! impl_int {
                 const G: usize = S - $Q;
                 // Combine offset (u << $Q) with previous quantization error e1
                 s += (((self >> G) as $A) << S) | (((self << $Q) | e1) as $U as $A);
+                overflow_check!(
+                    (s >> $Q) as $T as $A == s >> $Q,
+                    "accumulator exceeds output range"
+                );
                 // Ord::clamp() is slow and checks
                 // This clamping truncates the lowest G bits of the value and the limits.
                 debug_assert_eq!(min & ((1 << G) - 1), 0);
@@ -144,6 +188,20 @@ macro_rules! impl_int {
                 (value * (1 << $Q).as_()).round().as_()
             }
         }
+
+        impl SaturatingMacc for $T {
+            #[inline]
+            fn macc_sat(self, s: Self::ACCU, min: Self, max: Self) -> Self {
+                let s = s.saturating_add((self as $A) << $Q) >> $Q;
+                s.clamp(min as $A, max as $A) as $T
+            }
+
+            #[inline]
+            fn macc_round(self, s: Self::ACCU, min: Self, max: Self) -> Self {
+                let s = s.saturating_add(((self as $A) << $Q) + (1 << ($Q - 1))) >> $Q;
+                s.clamp(min as $A, max as $A) as $T
+            }
+        }
     };
 }
 // Q2.X chosen to be able to exactly and inclusively represent -2 as `-1 << X + 1`
@@ -153,3 +211,144 @@ impl_int!(i8, u8, i16, 6);
 impl_int!(i16, u16, i32, 14);
 impl_int!(i32, u32, i64, 30);
 impl_int!(i64, u64, i128, 62);
+
+/// Rounding and saturating fixed point shift and scale primitives
+///
+/// ```
+/// # use idsp::Shift;
+/// assert_eq!(5i32.shr_round(1), 2);
+/// assert_eq!(7i32.shr_round(1), 4);
+/// assert_eq!((-5i32).shr_round(1), -2);
+/// assert_eq!((-6i32).shr_round(2), -2);
+/// assert_eq!(0x4000_0000i32.shl_saturating(1), i32::MAX);
+/// assert_eq!((-5i8).shl_saturating(5), i8::MIN);
+/// assert_eq!((-5i8).shl_saturating(4), -80);
+/// assert_eq!((3i32 << 29).mul_round(3 << 29, 31), 9 << 27);
+/// assert_eq!(i32::MIN.mul_round(i32::MIN, 31), i32::MAX);
+/// ```
+pub trait Shift: Copy {
+    /// Arithmetic shift right by `n < BITS` with rounding half to even
+    fn shr_round(self, n: u32) -> Self;
+
+    /// Shift left by `n`, saturating at the numeric bounds
+    fn shl_saturating(self, n: u32) -> Self;
+
+    /// Fixed point multiplication `(self*other) >> n`, rounding half to even
+    /// and saturating, `n < 2*BITS`
+    fn mul_round(self, other: Self, n: u32) -> Self;
+}
+
+// Arithmetic shift right with rounding half to even
+macro_rules! shr_round {
+    ($x:expr, $n:expr, $T:ty) => {{
+        let (x, n): ($T, u32) = ($x, $n);
+        if n == 0 {
+            x
+        } else {
+            let q = x >> n;
+            let r = x & ((1 << n) - 1);
+            let half = 1 << (n - 1);
+            q + (r > half || (r == half && q & 1 == 1)) as $T
+        }
+    }};
+}
+
+macro_rules! impl_shift {
+    ($T:ty, $A:ty) => {
+        impl Shift for $T {
+            #[inline]
+            fn shr_round(self, n: u32) -> Self {
+                shr_round!(self, n, $T)
+            }
+
+            #[inline]
+            fn shl_saturating(self, n: u32) -> Self {
+                if self == 0 {
+                    0
+                } else if n < <$T>::BITS && (self << n) >> n == self {
+                    self << n
+                } else if self < 0 {
+                    <$T>::MIN
+                } else {
+                    <$T>::MAX
+                }
+            }
+
+            #[inline]
+            fn mul_round(self, other: Self, n: u32) -> Self {
+                let p = shr_round!(self as $A * other as $A, n, $A);
+                p.clamp(<$T>::MIN as $A, <$T>::MAX as $A) as $T
+            }
+        }
+    };
+}
+impl_shift!(i8, i16);
+impl_shift!(i16, i32);
+impl_shift!(i32, i64);
+impl_shift!(i64, i128);
+
+/// Solve `a x = b` by Gaussian elimination with partial pivoting.
+pub(crate) fn solve<const M: usize>(mut a: [[f64; M]; M], mut b: [f64; M]) -> [f64; M] {
+    for i in 0..M {
+        let p = (i..M)
+            .max_by(|j, k| Float::abs(a[*j][i]).total_cmp(&Float::abs(a[*k][i])))
+            .unwrap();
+        a.swap(i, p);
+        b.swap(i, p);
+        let (ai, aj) = a.split_at_mut(i + 1);
+        let ai = &ai[i];
+        for (aj, j) in aj.iter_mut().zip(i + 1..) {
+            let r = aj[i] / ai[i];
+            for (ajk, aik) in aj[i..].iter_mut().zip(ai[i..].iter()) {
+                *ajk -= r * aik;
+            }
+            b[j] -= r * b[i];
+        }
+    }
+    let mut x = [0.0; M];
+    for i in (0..M).rev() {
+        x[i] = (b[i] - (i + 1..M).map(|k| a[i][k] * x[k]).sum::<f64>()) / a[i][i];
+    }
+    x
+}
+
+/// Integer square root, rounded down
+pub(crate) fn isqrt(x: u64) -> u64 {
+    let mut r = x;
+    let mut y = 0;
+    // Largest power of four not exceeding `x`
+    let mut b = if x == 0 {
+        0
+    } else {
+        1 << ((63 - x.leading_zeros()) & !1)
+    };
+    while b != 0 {
+        if r >= y + b {
+            r -= y + b;
+            y = (y >> 1) + b;
+        } else {
+            y >>= 1;
+        }
+        b >>= 2;
+    }
+    y
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn isqrt_exact() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let edge = [0, 1, 2, 3, 4, 15, 16, 17, u32::MAX as u64, u64::MAX];
+        for x in edge
+            .into_iter()
+            .chain((0..10_000).map(|_| rng.gen::<u64>() >> rng.gen_range(0..64)))
+        {
+            let y = isqrt(x) as u128;
+            assert!(y * y <= x as u128 && (y + 1) * (y + 1) > x as u128, "{x}");
+        }
+    }
+}
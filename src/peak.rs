@@ -0,0 +1,143 @@
+use crate::Filter;
+
+/// Peak detector decay mode
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Decay {
+    /// Exponential decay towards zero by the given fraction of the peak
+    /// value per sample in units of `1 << 32`
+    Exponential(u32),
+    /// Linear decay towards zero by the given amount per sample
+    Linear(u32),
+}
+
+impl Default for Decay {
+    fn default() -> Self {
+        Self::Linear(0)
+    }
+}
+
+/// Peak detector with decay
+///
+/// The output follows rising inputs immediately and decays towards
+/// zero otherwise. The input is typically a magnitude (e.g.
+/// `x.saturating_abs()`) for envelope or overload detection.
+///
+/// ```
+/// # use idsp::{Decay, Filter, Peak};
+/// let mut p = Peak::default();
+/// let y: Vec<_> = [0, 100, 0, 0, 50, 0]
+///     .iter()
+///     .map(|x| p.update(*x, &Decay::Linear(20)))
+///     .collect();
+/// assert_eq!(y, [0, 100, 80, 60, 50, 30]);
+/// let y: Vec<_> = [100, 0, 0]
+///     .iter()
+///     .map(|x| p.update(*x, &Decay::Exponential(1 << 31)))
+///     .collect();
+/// assert_eq!(y, [100, 50, 25]);
+/// p.reset();
+/// assert_eq!(p.get(), 0);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Peak {
+    y: i32,
+}
+
+impl Peak {
+    /// Reset the peak value to zero.
+    pub fn reset(&mut self) {
+        self.y = 0;
+    }
+}
+
+impl Filter for Peak {
+    type Config = Decay;
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        let y = match *k {
+            Decay::Exponential(k) => self.y - ((self.y as i64 * k as i64) >> 32) as i32,
+            Decay::Linear(k) => (self.y as i64 - k as i64).max(self.y.min(0) as _) as i32,
+        };
+        self.y = y.max(x);
+        self.y
+    }
+
+    fn get(&self) -> i32 {
+        self.y
+    }
+
+    fn set(&mut self, x: i32) {
+        self.y = x;
+    }
+}
+
+/// Overload statistics
+///
+/// Tracks the peak absolute value, the number of clipped samples, and the
+/// number of samples since the last clipped sample of a signal path until
+/// explicitly reset.
+/// A sample is clipped if its absolute value reaches the given limit,
+/// e.g. `i32::MAX` after a saturating operation.
+///
+/// ```
+/// # use idsp::Overload;
+/// let mut o = Overload::default();
+/// for x in [3, -10, 5, 10, 0, -7] {
+///     o.update(x, 10);
+/// }
+/// assert_eq!(o.peak(), 10);
+/// assert_eq!(o.clips(), 2);
+/// assert_eq!(o.since(), Some(2));
+/// o.reset();
+/// assert_eq!((o.peak(), o.clips(), o.since()), (0, 0, None));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overload {
+    peak: u32,
+    clips: u32,
+    since: Option<u32>,
+}
+
+impl Overload {
+    /// Ingest a new sample
+    ///
+    /// # Args
+    /// * `x`: Signal sample
+    /// * `limit`: Clip level, `limit > 0`
+    ///
+    /// # Returns
+    /// Whether the sample is clipped.
+    pub fn update(&mut self, x: i32, limit: i32) -> bool {
+        let a = x.unsigned_abs();
+        self.peak = self.peak.max(a);
+        let clip = a >= limit as u32;
+        self.since = if clip {
+            self.clips = self.clips.saturating_add(1);
+            Some(0)
+        } else {
+            self.since.map(|s| s.saturating_add(1))
+        };
+        clip
+    }
+
+    /// Peak absolute value
+    pub fn peak(&self) -> u32 {
+        self.peak
+    }
+
+    /// Number of clipped samples (saturating)
+    pub fn clips(&self) -> u32 {
+        self.clips
+    }
+
+    /// Number of samples since the last clipped sample (saturating)
+    ///
+    /// `None` if no sample was clipped since the last reset.
+    pub fn since(&self) -> Option<u32> {
+        self.since
+    }
+
+    /// Clear all statistics.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
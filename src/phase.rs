@@ -0,0 +1,168 @@
+use num_traits::{AsPrimitive, Float, FloatConst};
+
+use crate::{atan2, cossin};
+
+/// Wrapping phase difference
+///
+/// Computes `a - b` for phases (full turn is `1 << 32`) along the shortest
+/// path, i.e. the result is in `-1 << 31..1 << 31` (half a turn).
+///
+/// ```
+/// # use idsp::phase_diff;
+/// assert_eq!(phase_diff(i32::MIN + 5, i32::MAX - 5), 11);
+/// assert_eq!(phase_diff(-3 << 29, 3 << 29), 1 << 30);
+/// ```
+pub fn phase_diff(a: i32, b: i32) -> i32 {
+    a.wrapping_sub(b)
+}
+
+/// Circular mean of phases
+///
+/// Computes the argument of the sum of unit vectors at the given phases.
+/// This is well defined across the wrap, unlike the arithmetic mean.
+/// For a zero resultant (e.g. uniformly distributed phases) the
+/// result is arbitrary.
+///
+/// # Args
+/// * `phase`: Phases, full turn is `1 << 32`
+///
+/// # Returns
+/// The mean phase or `None` for an empty slice.
+///
+/// ```
+/// # use idsp::phase_mean;
+/// let m = phase_mean(&[i32::MAX - (1 << 20), i32::MIN + (3 << 20)]).unwrap();
+/// assert!((m - (i32::MIN + (1 << 20))).abs() < 1 << 12);
+/// assert_eq!(phase_mean(&[]), None);
+/// ```
+pub fn phase_mean(phase: &[i32]) -> Option<i32> {
+    if phase.is_empty() {
+        return None;
+    }
+    let (x, y) = phase.iter().fold((0i64, 0i64), |(x, y), p| {
+        let (c, s) = cossin(*p);
+        (x + c as i64, y + s as i64)
+    });
+    let shift = phase.len().next_power_of_two().trailing_zeros();
+    Some(atan2((y >> shift) as _, (x >> shift) as _))
+}
+
+/// Shortest path phase interpolation
+///
+/// Interpolates linearly from phase `a` to phase `b` along the
+/// shorter arc.
+///
+/// # Args
+/// * `a`: Start phase, full turn is `1 << 32`
+/// * `b`: End phase
+/// * `t`: Interpolation fraction in units of `1 << 32`
+///
+/// # Returns
+/// Interpolated phase
+///
+/// ```
+/// # use idsp::phase_lerp;
+/// assert_eq!(phase_lerp(i32::MAX - 9, i32::MIN + 10, 1 << 31), i32::MIN);
+/// assert_eq!(phase_lerp(0, 1 << 30, 1 << 30), 1 << 28);
+/// ```
+pub fn phase_lerp(a: i32, b: i32, t: u32) -> i32 {
+    a.wrapping_add(((phase_diff(b, a) as i64 * t as i64) >> 32) as i32)
+}
+
+fn word_scale<W, F: Float>() -> F {
+    F::from(2)
+        .unwrap()
+        .powi(8 * core::mem::size_of::<W>() as i32)
+}
+
+/// Convert turns to a phase word
+///
+/// A full turn is `1 << 32` for `i32` and `1 << 64` for `i64` phase words.
+/// The result wraps.
+///
+/// ```
+/// # use idsp::phase_from_turns;
+/// assert_eq!(phase_from_turns::<i32, _>(0.25), 1 << 30);
+/// assert_eq!(phase_from_turns::<i32, _>(-1.75f32), 1 << 30);
+/// assert_eq!(phase_from_turns::<i64, _>(-0.125), -1 << 61);
+/// ```
+pub fn phase_from_turns<W, F>(turns: F) -> W
+where
+    W: 'static + Copy,
+    F: Float + AsPrimitive<W>,
+{
+    ((turns - turns.round()) * word_scale::<W, F>()).as_()
+}
+
+/// Convert a phase word to turns
+///
+/// ```
+/// # use idsp::phase_to_turns;
+/// assert_eq!(phase_to_turns::<_, f64>(-1i32 << 30), -0.25);
+/// ```
+pub fn phase_to_turns<W, F>(phase: W) -> F
+where
+    W: AsPrimitive<F>,
+    F: 'static + Float,
+{
+    phase.as_() / word_scale::<W, F>()
+}
+
+/// Convert radians to a phase word
+///
+/// ```
+/// # use idsp::phase_from_radians;
+/// assert_eq!(phase_from_radians::<i32, _>(core::f64::consts::FRAC_PI_2), 1 << 30);
+/// ```
+pub fn phase_from_radians<W, F>(radians: F) -> W
+where
+    W: 'static + Copy,
+    F: Float + FloatConst + AsPrimitive<W>,
+{
+    phase_from_turns(radians / F::TAU())
+}
+
+/// Convert a phase word to radians
+///
+/// ```
+/// # use idsp::phase_to_radians;
+/// assert_eq!(phase_to_radians::<_, f32>(i32::MIN), -core::f32::consts::PI);
+/// ```
+pub fn phase_to_radians<W, F>(phase: W) -> F
+where
+    W: AsPrimitive<F>,
+    F: 'static + Float + FloatConst,
+{
+    phase_to_turns::<W, F>(phase) * F::TAU()
+}
+
+/// Convert a frequency in Hz to a frequency word (phase increment per sample)
+///
+/// The result wraps (aliases) for frequencies beyond the Nyquist frequency.
+///
+/// ```
+/// # use idsp::frequency_from_hz;
+/// assert_eq!(frequency_from_hz::<i32, _>(12.5e3, 100e3), 1 << 29);
+/// assert_eq!(frequency_from_hz::<i32, _>(-75e3, 100e3), 1 << 30);
+/// ```
+pub fn frequency_from_hz<W, F>(frequency: F, sample_rate: F) -> W
+where
+    W: 'static + Copy,
+    F: Float + AsPrimitive<W>,
+{
+    phase_from_turns(frequency / sample_rate)
+}
+
+/// Convert a frequency word (phase increment per sample) to Hz
+///
+/// ```
+/// # use idsp::frequency_to_hz;
+/// assert_eq!(frequency_to_hz(1i64 << 60, 1.6e6), 1e5);
+/// ```
+pub fn frequency_to_hz<W, F>(frequency: W, sample_rate: F) -> F
+where
+    W: AsPrimitive<F>,
+    F: 'static + Float,
+{
+    phase_to_turns::<W, F>(frequency) * sample_rate
+}
@@ -64,6 +64,13 @@ impl PLL {
             let dx = x.wrapping_sub(self.x);
             self.x = x;
             let df = dx.wrapping_sub((self.f >> 32) as i32) as i64 * k as i64;
+            overflow_check!(
+                self.f
+                    .checked_add(df)
+                    .and_then(|f| f.checked_add(df))
+                    .is_some(),
+                "PLL frequency exceeds Nyquist"
+            );
             self.f = self.f.wrapping_add(df);
             self.y = self.y.wrapping_add(self.f);
             self.f = self.f.wrapping_add(df);
@@ -114,10 +121,10 @@ mod tests {
             x = x.wrapping_add(f0);
             p.update(Some(x), k);
             if i > n / 4 {
-                assert_eq!(p.frequency().wrapping_sub(f0).abs() <= 1, true);
+                assert!(p.frequency().wrapping_sub(f0).abs() <= 1);
             }
             if i > n / 2 {
-                assert_eq!(p.phase().wrapping_sub(x).abs() <= 1, true);
+                assert!(p.phase().wrapping_sub(x).abs() <= 1);
             }
         }
     }
@@ -0,0 +1,197 @@
+/// Linear feedback shift register (LFSR) pseudo-random binary sequence generator
+///
+/// A Galois LFSR with selectable feedback polynomial and width.
+/// With a primitive polynomial (e.g. the `PRBS*` constants) the output is a
+/// maximum length sequence with period `(1 << n) - 1`.
+///
+/// The output is available as a bit stream (see [`Iterator`]) or as
+/// `±i32::MAX` samples.
+///
+/// ```
+/// # use idsp::Lfsr;
+/// let mut l = Lfsr::new(Lfsr::PRBS7);
+/// assert_eq!(l.period(), 127);
+/// let b: Vec<_> = l.by_ref().take(8).collect();
+/// assert_eq!(b, [true, false, false, false, false, false, true, true]);
+/// assert_eq!(l.sample(), -i32::MAX);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Lfsr {
+    state: u32,
+    taps: u32,
+}
+
+impl Lfsr {
+    /// PRBS7: `x^7 + x^6 + 1`
+    pub const PRBS7: u32 = 0x60;
+    /// PRBS9: `x^9 + x^5 + 1`
+    pub const PRBS9: u32 = 0x110;
+    /// PRBS11: `x^11 + x^9 + 1`
+    pub const PRBS11: u32 = 0x500;
+    /// PRBS15: `x^15 + x^14 + 1`
+    pub const PRBS15: u32 = 0x6000;
+    /// PRBS20: `x^20 + x^3 + 1`
+    pub const PRBS20: u32 = 0x8_0004;
+    /// PRBS23: `x^23 + x^18 + 1`
+    pub const PRBS23: u32 = 0x42_0000;
+    /// PRBS31: `x^31 + x^28 + 1`
+    pub const PRBS31: u32 = 0x4800_0000;
+
+    /// Create a new LFSR
+    ///
+    /// # Args
+    /// * `taps`: Feedback mask. For a polynomial `x^n + ... + x^k + ... + 1`
+    ///   bit `k - 1` is set. The highest set bit determines the width `n`.
+    pub fn new(taps: u32) -> Self {
+        Self::with_seed(taps, 1)
+    }
+
+    /// Create a new LFSR with a given initial state
+    ///
+    /// # Args
+    /// * `taps`: Feedback mask
+    /// * `seed`: Initial state, non-zero, less than `1 << n`
+    pub fn with_seed(taps: u32, seed: u32) -> Self {
+        debug_assert!(taps != 0);
+        debug_assert!(seed != 0 && seed <= u32::MAX >> taps.leading_zeros());
+        Self { state: seed, taps }
+    }
+
+    /// Sequence period `(1 << n) - 1` (for a primitive polynomial)
+    pub fn period(&self) -> u32 {
+        u32::MAX >> self.taps.leading_zeros()
+    }
+
+    /// The current output bit
+    pub fn bit(&self) -> bool {
+        self.state & 1 != 0
+    }
+
+    /// The current state
+    pub fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Advance to the next bit.
+    pub fn step(&mut self) {
+        self.state = (self.state >> 1) ^ (self.taps & 0u32.wrapping_sub(self.state & 1));
+    }
+
+    /// Return the current output bit as a `±i32::MAX` sample and advance.
+    pub fn sample(&mut self) -> i32 {
+        if self.next().unwrap() {
+            i32::MAX
+        } else {
+            -i32::MAX
+        }
+    }
+}
+
+impl Iterator for Lfsr {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        let b = self.bit();
+        self.step();
+        Some(b)
+    }
+}
+
+/// PRBS correlator
+///
+/// Multiplies the input with a locally generated pseudo-random binary
+/// sequence (PRBS, a maximum length sequence from a [`Lfsr`]) and
+/// integrates over the code period. The current chip is available
+/// to drive a binary excitation. Because the autocorrelation of the
+/// sequence is `N` at zero lag and `-1` at all other lags of the period
+/// `N`, this can identify a small response to the excitation buried in
+/// noise (system identification), or despread a spread spectrum signal.
+///
+/// ```
+/// # use idsp::PrbsCorrelator;
+/// # use idsp::Lfsr;
+/// let mut c = PrbsCorrelator::new(Lfsr::PRBS7); // period 127
+/// let mut x = [0; 2];
+/// let mut y = None;
+/// for _ in 0..2 * 127 {
+///     let chip = if c.chip() { 1 } else { -1 };
+///     // Response: delayed by one sample
+///     x = [chip, x[0]];
+///     y = c.update(x[1] + 2 * chip).or(y);
+/// }
+/// assert_eq!(y, Some(2 * 127 - 1));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PrbsCorrelator {
+    lfsr: Lfsr,
+    index: u32,
+    sum: i64,
+}
+
+impl PrbsCorrelator {
+    /// Create a new correlator
+    ///
+    /// # Args
+    /// * `taps`: LFSR feedback mask of a maximum length sequence, see [`Lfsr`].
+    pub fn new(taps: u32) -> Self {
+        Self {
+            lfsr: Lfsr::new(taps),
+            index: 0,
+            sum: 0,
+        }
+    }
+
+    /// The current chip
+    pub fn chip(&self) -> bool {
+        self.lfsr.bit()
+    }
+
+    /// Ingest a new sample and advance the sequence.
+    ///
+    /// # Returns
+    /// The correlation `sum(x[n]*c[n])` over a complete period of the
+    /// sequence (with chips `c = 1` for `true` and `-1` for `false`)
+    /// at the end of each period.
+    pub fn update(&mut self, x: i32) -> Option<i64> {
+        self.sum += if self.chip() { x as i64 } else { -(x as i64) };
+        self.lfsr.step();
+        self.index += 1;
+        (self.index == self.period()).then(|| {
+            self.index = 0;
+            core::mem::take(&mut self.sum)
+        })
+    }
+
+    /// Code period in chips
+    pub fn period(&self) -> u32 {
+        self.lfsr.period()
+    }
+
+    /// Index of the current chip within the period
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn maximum_length() {
+        for taps in [
+            Lfsr::PRBS7,
+            Lfsr::PRBS9,
+            Lfsr::PRBS11,
+            Lfsr::PRBS15,
+            Lfsr::PRBS20,
+        ] {
+            let mut l = Lfsr::new(taps);
+            let s0 = l.state();
+            for i in 1..=l.period() {
+                l.step();
+                assert_eq!(l.state() == s0, i == l.period());
+            }
+        }
+    }
+}
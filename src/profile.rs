@@ -0,0 +1,197 @@
+use crate::MovingAverage;
+
+/// [`Trapezoid`] and [`SCurve`] configuration
+///
+/// Velocity and acceleration are in units of position LSB per sample
+/// (per sample squared) with 32 fractional bits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrapezoidConfig {
+    /// Maximum velocity, `velocity > 0`
+    pub velocity: i64,
+    /// Maximum acceleration, `acceleration > 0`
+    pub acceleration: i64,
+}
+
+/// Trapezoidal setpoint profile generator
+///
+/// Moves the output position towards a target position with limited
+/// velocity and acceleration (a trapezoidal velocity profile). The target
+/// can change at any time, also during a move. The output reaches the
+/// target exactly and without overshoot.
+///
+/// ```
+/// # use idsp::{Trapezoid, TrapezoidConfig};
+/// let k = TrapezoidConfig { velocity: 10 << 32, acceleration: 1 << 32 };
+/// let mut t = Trapezoid::default();
+/// let y: Vec<_> = (0..40).map(|_| t.update(200, &k)).collect();
+/// assert_eq!(y[..5], [1, 3, 6, 10, 15]);
+/// assert_eq!(y[10..14], [65, 75, 85, 95]);
+/// assert_eq!(y[29..], [200; 11]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trapezoid {
+    /// Position with 32 fractional bits
+    position: i64,
+    /// Velocity with 32 fractional bits
+    velocity: i64,
+}
+
+impl Trapezoid {
+    /// Compute the next output position.
+    ///
+    /// # Args
+    /// * `target`: Target position
+    /// * `k`: Limits
+    ///
+    /// # Returns
+    /// The new position
+    pub fn update(&mut self, target: i32, k: &TrapezoidConfig) -> i32 {
+        let (v, a) = (self.velocity, k.acceleration);
+        let d = ((target as i64) << 32) - self.position;
+        let dir = d.signum();
+        // Distance to stop from the current velocity
+        let brake = ((v as i128 * v as i128) / (2 * a as i128)) as i64 + v.abs() / 2;
+        let v = if v.signum() == dir && d.abs() <= brake {
+            v - dir * a
+        } else {
+            v + dir * a
+        }
+        .clamp(-k.velocity, k.velocity);
+        if d.abs() <= v.abs() || (d.abs() <= a && self.velocity.abs() <= a) {
+            self.position = (target as i64) << 32;
+            self.velocity = 0;
+        } else {
+            self.position += v;
+            self.velocity = v;
+        }
+        self.get()
+    }
+
+    /// The current position
+    pub fn get(&self) -> i32 {
+        (self.position >> 32) as i32
+    }
+
+    /// The current velocity with 32 fractional bits
+    pub fn velocity(&self) -> i64 {
+        self.velocity
+    }
+
+    /// Set the position and stop.
+    pub fn set(&mut self, position: i32) {
+        self.position = (position as i64) << 32;
+        self.velocity = 0;
+    }
+}
+
+/// Jerk-limited (S-curve) setpoint profile generator
+///
+/// A [`Trapezoid`] profile smoothed with an `N` sample [`MovingAverage`].
+/// Velocity and acceleration limits are those of the trapezoidal profile.
+/// Acceleration changes are spread over `N` samples, limiting the jerk to
+/// `acceleration/N` (`2*acceleration/N` where the acceleration reverses
+/// without a constant velocity phase). The output lags the trapezoidal profile by `(N - 1)/2`
+/// samples, is monotonic during a move and settles
+/// exactly on the target `N - 1` samples after the trapezoidal profile.
+///
+/// ```
+/// # use idsp::{SCurve, TrapezoidConfig};
+/// let k = TrapezoidConfig { velocity: 10 << 32, acceleration: 1 << 32 };
+/// let mut s = SCurve::<4>::default();
+/// let y: Vec<_> = (0..40).map(|_| s.update(200, &k)).collect();
+/// assert_eq!(y[..6], [0, 1, 2, 5, 8, 13]);
+/// assert_eq!(y[32..], [200; 8]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SCurve<const N: usize> {
+    trapezoid: Trapezoid,
+    average: MovingAverage<i64, N>,
+    velocity: i64,
+}
+
+impl<const N: usize> SCurve<N> {
+    /// Compute the next output position.
+    ///
+    /// # Args
+    /// * `target`: Target position
+    /// * `k`: Velocity and acceleration limits
+    ///
+    /// # Returns
+    /// The new position
+    pub fn update(&mut self, target: i32, k: &TrapezoidConfig) -> i32 {
+        self.trapezoid.update(target, k);
+        let p = self.average.get();
+        self.velocity = self.average.update(self.trapezoid.position) - p;
+        self.get()
+    }
+
+    /// The current position
+    pub fn get(&self) -> i32 {
+        (self.average.get() >> 32) as i32
+    }
+
+    /// The current velocity with 32 fractional bits
+    pub fn velocity(&self) -> i64 {
+        self.velocity
+    }
+
+    /// Set the position and stop.
+    pub fn set(&mut self, position: i32) {
+        self.trapezoid.set(position);
+        self.average.set(self.trapezoid.position);
+        self.velocity = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        let k = TrapezoidConfig {
+            velocity: 1000 << 32,
+            acceleration: 3 << 30,
+        };
+        for target in [1, -7, 12345, -1 << 20, 1 << 24] {
+            let mut t = Trapezoid::default();
+            t.set(17);
+            let mut v = 0i64;
+            let mut done = false;
+            for _ in 0..100_000 {
+                let p0 = t.get();
+                let p = t.update(target, &k);
+                assert!((p - target).abs() <= (p0 - target).abs());
+                if p == target && t.velocity() == 0 {
+                    done = true;
+                    break;
+                }
+                assert!(t.velocity().abs() <= k.velocity);
+                assert!((t.velocity() - v).abs() <= k.acceleration);
+                v = t.velocity();
+            }
+            assert!(done);
+        }
+    }
+
+    #[test]
+    fn jerk() {
+        let k = TrapezoidConfig {
+            velocity: 100 << 32,
+            acceleration: 1 << 32,
+        };
+        let mut s = SCurve::<16>::default();
+        let (mut v, mut a) = (0i64, 0i64);
+        for _ in 0..1000 {
+            let p0 = s.get();
+            let p = s.update(-10000, &k);
+            assert!(p <= p0);
+            let a1 = s.velocity() - v;
+            assert!(a1.abs() <= k.acceleration + 1);
+            assert!((a1 - a).abs() <= (k.acceleration >> 3) + 2);
+            (v, a) = (s.velocity(), a1);
+        }
+        assert_eq!(s.get(), -10000);
+        assert_eq!(s.velocity(), 0);
+    }
+}
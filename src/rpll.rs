@@ -131,7 +131,7 @@ mod test {
         fn run(&mut self, n: usize) -> (Vec<f32>, Vec<f32>) {
             assert!(self.period >= 1 << self.rpll.dt2);
             assert!(self.period < 1 << self.shift_frequency);
-            assert!(self.period < 1 << self.shift_phase + 1);
+            assert!(self.period < 1 << (self.shift_phase + 1));
 
             let mut y = Vec::<f32>::new();
             let mut f = Vec::<f32>::new();
@@ -155,7 +155,7 @@ mod test {
                 // phase error
                 y.push(yi.wrapping_sub(y_ref) as f32 / 2f32.powi(32));
 
-                let p_ref = 1 << 32 + self.rpll.dt2;
+                let p_ref = 1 << (32 + self.rpll.dt2);
                 let p_sig = fi as u64 * self.period as u64;
                 // relative frequency error
                 f.push(
@@ -169,8 +169,8 @@ mod test {
         }
 
         fn measure(&mut self, n: usize, limits: [f32; 4]) {
-            let t_settle = (1 << self.shift_frequency - self.rpll.dt2 + 4)
-                + (1 << self.shift_phase - self.rpll.dt2 + 4);
+            let t_settle = (1 << (self.shift_frequency - self.rpll.dt2 + 4))
+                + (1 << (self.shift_phase - self.rpll.dt2 + 4));
             self.run(t_settle);
 
             let (y, f) = self.run(n);
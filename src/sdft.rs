@@ -0,0 +1,109 @@
+use crate::{Complex, ComplexExt, Delay, MulScaled};
+
+/// Sliding DFT
+///
+/// Tracks `K` bins of the `N` point DFT of the last `N` samples,
+/// updated with every sample in O(K).
+///
+/// The input is mixed down with the bin frequencies and the mixer output
+/// is summed over a sliding window (modulated sliding DFT).
+/// The mixer phase is an exact function of the sample index modulo `N` and
+/// the sum is exact. The sample leaving the window is mixed
+/// identically to when it entered. Its contribution is removed exactly,
+/// errors do not accumulate and the recursion is unconditionally stable.
+///
+/// ```
+/// # use idsp::{cossin, SlidingDft};
+/// let mut s = SlidingDft::<16, 2>::new([1, 2]);
+/// for i in 0..32 {
+///     s.update(cossin(((i as i32) << 28).wrapping_add(1 << 30)).0 >> 1);
+/// }
+/// let (x1, x2) = (s.bin(0), s.bin(1));
+/// // Amplitude 1/2 full scale, phase pi/2 at the window start
+/// assert!(x1.re.abs() < 1 << 12);
+/// assert!((x1.im - (1 << 29)).abs() < 1 << 16);
+/// assert!(x2.re.abs() < 1 << 8 && x2.im.abs() < 1 << 8);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SlidingDft<const N: usize, const K: usize> {
+    x: Delay<i32, N>,
+    /// Index of the next sample modulo `N`
+    n: usize,
+    bins: [usize; K],
+    sums: [Complex<i64>; K],
+}
+
+impl<const N: usize, const K: usize> SlidingDft<N, K> {
+    /// Create a new sliding DFT
+    ///
+    /// # Args
+    /// * `bins`: Bin indices to track, `bin < N`
+    pub fn new(bins: [usize; K]) -> Self {
+        debug_assert!(bins.iter().all(|k| *k < N));
+        Self {
+            x: Delay::new(0),
+            n: 0,
+            bins,
+            sums: [Complex::new(0, 0); K],
+        }
+    }
+
+    /// Phase of bin `k` at sample index `n`, full turn is `1 << 32`
+    fn phase(k: usize, n: usize) -> i32 {
+        (((((k * n) % N) as u64) << 32) / N as u64) as u32 as i32
+    }
+
+    /// Ingest a new sample.
+    pub fn update(&mut self, x: i32) {
+        let old = self.x.push(x);
+        for (k, s) in self.bins.iter().zip(self.sums.iter_mut()) {
+            let lo = Complex::from_angle(Self::phase(*k, self.n).wrapping_neg());
+            let new = lo.mul_scaled(x);
+            let old = lo.mul_scaled(old);
+            s.re += new.re as i64 - old.re as i64;
+            s.im += new.im as i64 - old.im as i64;
+        }
+        self.n = if self.n + 1 == N { 0 } else { self.n + 1 };
+    }
+
+    /// The DFT bin value
+    ///
+    /// # Args
+    /// * `j`: Index into the tracked bins
+    ///
+    /// # Returns
+    /// The DFT bin of the last `N` samples with the oldest sample at
+    /// index 0, normalized by `1/N`.
+    /// A sinusoid of amplitude `A` at the bin frequency yields
+    /// magnitude `A/2`.
+    pub fn bin(&self, j: usize) -> Complex<i32> {
+        let s = self.sums[j];
+        let s = Complex::new((s.re / N as i64) as i32, (s.im / N as i64) as i32);
+        // The window starts at the next sample index modulo N
+        s.mul_scaled(Complex::from_angle(Self::phase(self.bins[j], self.n)))
+    }
+
+    /// The tracked bin indices
+    pub fn bins(&self) -> &[usize; K] {
+        &self.bins
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn stable() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut s = SlidingDft::<7, 3>::new([0, 1, 3]);
+        for _ in 0..100_000 {
+            s.update(rng.gen());
+        }
+        for _ in 0..7 {
+            s.update(0);
+        }
+        assert_eq!(s.sums, [Complex::new(0, 0); 3]);
+    }
+}
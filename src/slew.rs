@@ -0,0 +1,56 @@
+use crate::Filter;
+
+/// Slew rate limiter
+///
+/// The output follows the input but changes by at most a given amount per
+/// sample, independently for rising and falling slopes.
+/// Usable on setpoints (bump-free changes) and on outputs (actuator
+/// protection).
+///
+/// ```
+/// # use idsp::{Filter, SlewLimiter};
+/// let mut s = SlewLimiter::default();
+/// let k = [3, 5]; // rise, fall
+/// let y: Vec<_> = [10, 10, 10, 10, 0, 0].iter().map(|x| s.update(*x, &k)).collect();
+/// assert_eq!(y, [3, 6, 9, 10, 5, 0]);
+/// assert!(!s.active());
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SlewLimiter {
+    y: i32,
+    active: bool,
+}
+
+impl SlewLimiter {
+    /// Whether the output was limited during the last update
+    pub fn active(&self) -> bool {
+        self.active
+    }
+}
+
+impl Filter for SlewLimiter {
+    /// Maximum change per sample `[rise, fall]`, both non-negative
+    type Config = [u32; 2];
+    fn update(&mut self, x: i32, k: &Self::Config) -> i32 {
+        let dy = x as i64 - self.y as i64;
+        let (dy, active) = if dy > k[0] as i64 {
+            (k[0] as i64, true)
+        } else if dy < -(k[1] as i64) {
+            (-(k[1] as i64), true)
+        } else {
+            (dy, false)
+        };
+        self.active = active;
+        self.y = (self.y as i64 + dy) as i32;
+        self.y
+    }
+
+    fn get(&self) -> i32 {
+        self.y
+    }
+
+    fn set(&mut self, x: i32) {
+        self.y = x;
+        self.active = false;
+    }
+}
@@ -0,0 +1,506 @@
+use num_traits::Float;
+
+use crate::Delay;
+
+/// Welford online mean and variance accumulator
+///
+/// Numerically stable streaming mean and variance for floating point
+/// samples. Accumulators can be merged, e.g. to combine statistics
+/// from multiple blocks.
+///
+/// ```
+/// # use idsp::Welford;
+/// let mut w = Welford::<f64>::default();
+/// for x in [1.0, 2.0, 3.0, 4.0] {
+///     w.update(x);
+/// }
+/// let mut v = Welford::default();
+/// for x in [1e9 + 1.0, 1e9 + 3.0] {
+///     v.update(x);
+/// }
+/// assert_eq!(v.mean(), 1e9 + 2.0);
+/// assert_eq!(v.variance(), Some(1.0));
+/// v.merge(&w);
+/// assert_eq!(w.count(), 4);
+/// assert_eq!(w.mean(), 2.5);
+/// assert_eq!(w.variance(), Some(1.25));
+/// assert_eq!(w.sample_variance(), Some(5.0 / 3.0));
+/// assert_eq!(v.count(), 6);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Welford<F> {
+    count: u64,
+    mean: F,
+    m2: F,
+}
+
+impl<F: Float> Welford<F> {
+    /// Add a sample.
+    pub fn update(&mut self, x: F) {
+        self.count += 1;
+        let d = x - self.mean;
+        self.mean = self.mean + d / F::from(self.count).unwrap();
+        self.m2 = self.m2 + d * (x - self.mean);
+    }
+
+    /// Merge the statistics of another accumulator.
+    pub fn merge(&mut self, other: &Self) {
+        let count = self.count + other.count;
+        if count == 0 {
+            return;
+        }
+        let (n, m) = (F::from(self.count).unwrap(), F::from(other.count).unwrap());
+        let d = other.mean - self.mean;
+        let nm = F::from(count).unwrap();
+        self.mean = self.mean + d * m / nm;
+        self.m2 = self.m2 + other.m2 + d * d * n * m / nm;
+        self.count = count;
+    }
+
+    /// The number of samples
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The mean
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+
+    /// The population variance (normalized by the number of samples)
+    ///
+    /// `None` without samples.
+    pub fn variance(&self) -> Option<F> {
+        (self.count > 0).then(|| self.m2 / F::from(self.count).unwrap())
+    }
+
+    /// The sample variance (normalized by the number of samples minus one)
+    ///
+    /// `None` with fewer than two samples.
+    pub fn sample_variance(&self) -> Option<F> {
+        (self.count > 1).then(|| self.m2 / F::from(self.count - 1).unwrap())
+    }
+
+    /// Reset the accumulator.
+    pub fn reset(&mut self) {
+        *self = Self {
+            count: 0,
+            mean: F::zero(),
+            m2: F::zero(),
+        };
+    }
+}
+
+/// Exact integer mean and variance accumulator
+///
+/// Accumulates the count, sum, and sum of squares of `i32` samples
+/// in wide integers. This is exact and does not overflow for up to
+/// `1 << 32` samples. Accumulators can be merged.
+///
+/// ```
+/// # use idsp::Moments;
+/// let mut m = Moments::default();
+/// for x in [i32::MAX, i32::MAX, i32::MIN + 1, i32::MIN + 1] {
+///     m.update(x);
+/// }
+/// assert_eq!(m.mean(), 0);
+/// assert_eq!(m.variance(), (i32::MAX as u64).pow(2));
+/// let mut n = Moments::default();
+/// n.update(3);
+/// m.merge(&n);
+/// assert_eq!(m.count(), 5);
+/// assert_eq!(m.sum(), 3);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Moments {
+    count: u64,
+    sum: i64,
+    sum2: i128,
+}
+
+impl Moments {
+    /// Add a sample.
+    pub fn update(&mut self, x: i32) {
+        self.count += 1;
+        self.sum += x as i64;
+        self.sum2 += x as i128 * x as i128;
+    }
+
+    /// Merge the statistics of another accumulator.
+    pub fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.sum += other.sum;
+        self.sum2 += other.sum2;
+    }
+
+    /// The number of samples
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The sum of the samples
+    pub fn sum(&self) -> i64 {
+        self.sum
+    }
+
+    /// The mean, rounded towards zero, zero if there are no samples
+    pub fn mean(&self) -> i32 {
+        if self.count == 0 {
+            0
+        } else {
+            (self.sum / self.count as i64) as _
+        }
+    }
+
+    /// The population variance (normalized by the number of samples),
+    /// rounded down, zero if there are no samples
+    pub fn variance(&self) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let n = self.count as i128;
+        let s = self.sum as i128;
+        ((n * self.sum2 - s * s) / (n * n)) as _
+    }
+
+    /// Reset the accumulator.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Exponentially weighted mean and variance tracker
+///
+/// Tracks the mean and variance of `i32` samples with forgetting
+/// factor `1 - 1/(1 << k)`, i.e. a time constant of `1 << k` samples.
+/// This never needs to be reset and is useful to continuously estimate
+/// the noise of a signal.
+///
+/// ```
+/// # use idsp::EwmaVariance;
+/// let mut e = EwmaVariance::default();
+/// for i in 0..1 << 12 {
+///     e.update(if i & 1 == 0 { 1003 } else { 997 }, 6);
+/// }
+/// assert!((e.mean() - 1000).abs() <= 1);
+/// assert!((e.variance() as i64 - 9).abs() <= 1);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EwmaVariance {
+    /// Mean with 32 fractional bits
+    mean: i64,
+    /// Variance with 32 fractional bits
+    var: i128,
+}
+
+impl EwmaVariance {
+    /// Add a sample.
+    ///
+    /// # Args
+    /// * `x`: Sample
+    /// * `k`: Forgetting factor exponent, `k < 32`
+    ///
+    /// # Returns
+    /// The new mean
+    pub fn update(&mut self, x: i32, k: u32) -> i32 {
+        let d = (((x as i64) << 32) as i128) - self.mean as i128;
+        self.mean += (d >> k) as i64;
+        // var = (1 - a)*(var + a*d**2)
+        let e = d >> 16;
+        let ad2 = (e * e) >> k;
+        self.var += ad2 - (ad2 >> k) - (self.var >> k);
+        self.mean()
+    }
+
+    /// The current mean
+    pub fn mean(&self) -> i32 {
+        (self.mean >> 32) as _
+    }
+
+    /// The current variance
+    pub fn variance(&self) -> u64 {
+        (self.var >> 32) as _
+    }
+
+    /// Set the mean and the variance.
+    pub fn set(&mut self, mean: i32, variance: u64) {
+        self.mean = (mean as i64) << 32;
+        self.var = (variance as i128) << 32;
+    }
+}
+
+/// [`Histogram`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistogramConfig {
+    /// Lower edge of the first bin
+    pub offset: i32,
+    /// Bin width is `1 << shift`, `shift < 32`
+    pub shift: u32,
+}
+
+/// Fixed bin histogram
+///
+/// Counts samples in `N` bins of equal power-of-two width. Counts saturate.
+/// Samples outside the bin range are counted separately.
+///
+/// ```
+/// # use idsp::{Histogram, HistogramConfig};
+/// let k = HistogramConfig { offset: -4, shift: 1 };
+/// let mut h = Histogram::<4>::default();
+/// for x in [-5, -4, -3, -2, 0, 1, 3, 4, 100] {
+///     h.update(x, &k);
+/// }
+/// assert_eq!(h.bins(), &[2, 1, 2, 1]);
+/// assert_eq!(h.outside(), [1, 2]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Histogram<const N: usize> {
+    bins: [u32; N],
+    outside: [u32; 2],
+}
+
+impl<const N: usize> Default for Histogram<N> {
+    fn default() -> Self {
+        Self {
+            bins: [0; N],
+            outside: [0; 2],
+        }
+    }
+}
+
+impl<const N: usize> Histogram<N> {
+    /// Count a sample.
+    pub fn update(&mut self, x: i32, k: &HistogramConfig) {
+        let i = (x as i64 - k.offset as i64) >> k.shift;
+        let c = if i < 0 {
+            &mut self.outside[0]
+        } else if i >= N as i64 {
+            &mut self.outside[1]
+        } else {
+            &mut self.bins[i as usize]
+        };
+        *c = c.saturating_add(1);
+    }
+
+    /// The bin counts
+    pub fn bins(&self) -> &[u32; N] {
+        &self.bins
+    }
+
+    /// The counts of samples below and above the bin range
+    pub fn outside(&self) -> [u32; 2] {
+        self.outside
+    }
+
+    /// Reset all counts to zero.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Overlapping Allan variance estimator
+///
+/// Streaming overlapping Allan variance of frequency samples (e.g. from
+/// [`crate::PLL`] or [`crate::RPLL`]) at `L` averaging times `tau = m*tau0`
+/// with `m = 1 << j` for `j in 0..L`, `tau0` being the sample interval.
+///
+/// The frequency samples are integrated into phase, a history of `N` phase
+/// samples is kept. All averaging times need `N > 1 << L`.
+///
+/// ```
+/// # use idsp::AllanVariance;
+/// let mut a = AllanVariance::<5, 2>::default();
+/// for i in 0..100 {
+///     a.update(if i & 1 == 0 { 3 } else { -3 });
+/// }
+/// assert_eq!(a.variance(0), Some(18.0));
+/// assert_eq!(a.variance(1), Some(0.0));
+/// assert_eq!(a.deviation(0), Some(18.0f64.sqrt()));
+/// a.reset();
+/// assert_eq!(a.variance(0), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AllanVariance<const N: usize, const L: usize> {
+    /// Phase history
+    x: Delay<i64, N>,
+    /// Number of samples, saturating
+    count: usize,
+    /// Sum of squared second differences of phase
+    sum: [u128; L],
+    /// Number of terms in each sum
+    terms: [u64; L],
+}
+
+impl<const N: usize, const L: usize> Default for AllanVariance<N, L> {
+    fn default() -> Self {
+        Self {
+            x: Delay::default(),
+            count: 0,
+            sum: [0; L],
+            terms: [0; L],
+        }
+    }
+}
+
+impl<const N: usize, const L: usize> AllanVariance<N, L> {
+    /// Add a frequency sample.
+    pub fn update(&mut self, y: i32) {
+        let x = self.x.tap(0).wrapping_add(y as i64);
+        self.x.push(x);
+        self.count = self.count.saturating_add(1);
+        for (j, (sum, terms)) in self.sum.iter_mut().zip(self.terms.iter_mut()).enumerate() {
+            let m = 1 << j;
+            if 2 * m >= N || 2 * m >= self.count {
+                break;
+            }
+            let d = x
+                .wrapping_sub(self.x.tap(m).wrapping_mul(2))
+                .wrapping_add(self.x.tap(2 * m));
+            *sum += (d as i128 * d as i128) as u128;
+            *terms += 1;
+        }
+    }
+
+    /// The Allan variance at `tau = (1 << j)*tau0`
+    ///
+    /// In units of the squared frequency samples.
+    /// `None` if there is not enough data.
+    pub fn variance(&self, j: usize) -> Option<f64> {
+        let terms = self.terms[j];
+        (terms > 0).then(|| {
+            let m = (1u64 << j) as f64;
+            self.sum[j] as f64 / (2.0 * m * m * terms as f64)
+        })
+    }
+
+    /// The Allan deviation at `tau = (1 << j)*tau0`
+    pub fn deviation(&self, j: usize) -> Option<f64> {
+        self.variance(j).map(Float::sqrt)
+    }
+
+    /// Reset the estimator.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Streaming linear regression
+///
+/// Online least squares fit of a line to samples taken at unit intervals
+/// with exponential forgetting. Use to estimate drift rates.
+///
+/// The time origin is at the most recent sample, the intercept is the fitted
+/// value at the most recent sample and the slope is per sample.
+///
+/// ```
+/// # use idsp::Regression;
+/// let mut r = Regression::<f64>::default();
+/// for i in 0..100 {
+///     r.update(3.0 + 0.5 * i as f64 + if i & 1 == 0 { 0.1 } else { -0.1 }, 0.99);
+/// }
+/// assert!((r.slope().unwrap() - 0.5).abs() < 1e-3);
+/// assert!((r.intercept().unwrap() - (3.0 + 0.5 * 99.0)).abs() < 0.1);
+/// assert!((r.predict(10.0).unwrap() - (3.0 + 0.5 * 109.0)).abs() < 0.1);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Regression<F> {
+    /// Weighted sums of `1, t, t**2, y, t*y`
+    s: [F; 5],
+}
+
+impl<F: Float> Regression<F> {
+    /// Add a sample.
+    ///
+    /// # Args
+    /// * `y`: New sample
+    /// * `lambda`: Forgetting factor, `0 < lambda <= 1`, the effective window
+    ///   length is `1/(1 - lambda)`. `lambda = 1` fits all samples equally.
+    pub fn update(&mut self, y: F, lambda: F) {
+        let [s0, st, stt, sy, sty] = self.s;
+        // Shift the time origin to the new sample and decay
+        self.s = [
+            (s0 * lambda) + F::one(),
+            (st - s0) * lambda,
+            (stt - st - st + s0) * lambda,
+            (sy * lambda) + y,
+            (sty - sy) * lambda,
+        ];
+    }
+
+    /// The slope per sample
+    ///
+    /// `None` if the fit is undetermined (fewer than two samples).
+    pub fn slope(&self) -> Option<F> {
+        let [s0, st, stt, sy, sty] = self.s;
+        let det = s0 * stt - st * st;
+        (det != F::zero()).then(|| (s0 * sty - st * sy) / det)
+    }
+
+    /// The fitted value at the most recent sample
+    ///
+    /// `None` if the fit is undetermined.
+    pub fn intercept(&self) -> Option<F> {
+        let [s0, st, _, sy, _] = self.s;
+        Some((sy - self.slope()? * st) / s0)
+    }
+
+    /// Extrapolate the fit `n` samples ahead of the most recent sample.
+    ///
+    /// `None` if the fit is undetermined.
+    pub fn predict(&self, n: F) -> Option<F> {
+        Some(self.intercept()? + self.slope()? * n)
+    }
+
+    /// Reset the fit.
+    pub fn reset(&mut self) {
+        self.s = [F::zero(); 5];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GaussianNoise;
+
+    #[test]
+    fn welford_degenerate() {
+        let mut w = Welford::<f64>::default();
+        assert_eq!((w.variance(), w.sample_variance()), (None, None));
+        w.update(3.0);
+        assert_eq!((w.variance(), w.sample_variance()), (Some(0.0), None));
+        w.update(5.0);
+        assert_eq!((w.variance(), w.sample_variance()), (Some(1.0), Some(2.0)));
+    }
+
+    #[test]
+    fn regression_degenerate() {
+        let mut r = Regression::<f64>::default();
+        assert_eq!(
+            (r.slope(), r.intercept(), r.predict(1.0)),
+            (None, None, None)
+        );
+        r.update(3.0, 0.9);
+        assert_eq!(
+            (r.slope(), r.intercept(), r.predict(1.0)),
+            (None, None, None)
+        );
+        r.update(4.0, 0.9);
+        assert!((r.slope().unwrap() - 1.0).abs() < 1e-12);
+        assert!((r.intercept().unwrap() - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn allan_white() {
+        let mut a = AllanVariance::<65, 6>::default();
+        let mut g = GaussianNoise::new(3);
+        let rms = 1 << 16;
+        for _ in 0..1 << 17 {
+            a.update(g.update(rms));
+        }
+        for j in 0..6 {
+            // White frequency noise: sigma**2/m
+            let v = a.variance(j).unwrap() * (1 << j) as f64 / (rms as f64).powi(2);
+            assert!((v - 1.0).abs() < 0.1, "{j} {v}");
+        }
+    }
+}
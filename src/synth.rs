@@ -0,0 +1,692 @@
+use num_traits::Float;
+
+use crate::{cossin, fir::Farrow, Xoshiro128};
+
+/// Tone parameters
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tone {
+    /// Frequency: phase increment per sample, full turn is `1 << 32`
+    pub frequency: i32,
+    /// Phase offset, full turn is `1 << 32`
+    pub phase: i32,
+    /// Amplitude, full scale is `i32::MAX`
+    pub amplitude: i32,
+}
+
+/// Multi-tone synthesizer
+///
+/// Sums the outputs of `N` numerically controlled oscillators
+/// `amplitude*cos(phase)` with individual frequencies, phase offsets and
+/// amplitudes. The sum saturates.
+///
+/// ```
+/// # use idsp::{MultiTone, Tone};
+/// let k = [
+///     Tone { frequency: 1 << 30, phase: 0, amplitude: 1 << 20 },
+///     Tone { frequency: i32::MIN, phase: 0, amplitude: 1 << 10 },
+/// ];
+/// let mut m = MultiTone::default();
+/// let y0 = [(1 << 20) + (1 << 10), -1 << 10, (-1 << 20) + (1 << 10), -1 << 10];
+/// for y0 in y0 {
+///     assert!((m.update(&k) - y0).abs() < 1 << 5);
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MultiTone<const N: usize> {
+    phase: [i32; N],
+}
+
+impl<const N: usize> Default for MultiTone<N> {
+    fn default() -> Self {
+        Self { phase: [0; N] }
+    }
+}
+
+impl<const N: usize> MultiTone<N> {
+    /// Compute the next output sample.
+    ///
+    /// # Args
+    /// * `k`: Tone parameters
+    pub fn update(&mut self, k: &[Tone; N]) -> i32 {
+        let mut y = 0i64;
+        for (p, k) in self.phase.iter_mut().zip(k.iter()) {
+            let c = cossin(p.wrapping_add(k.phase)).0;
+            y += (c as i64 * k.amplitude as i64) >> 31;
+            *p = p.wrapping_add(k.frequency);
+        }
+        y.clamp(i32::MIN as _, i32::MAX as _) as _
+    }
+
+    /// The oscillator phases (excluding the offsets)
+    pub fn phase(&self) -> &[i32; N] {
+        &self.phase
+    }
+
+    /// Reset the oscillator phases to zero.
+    pub fn reset(&mut self) {
+        self.phase = [0; N];
+    }
+}
+
+/// Multisine phase selection
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MultisinePhase {
+    /// Schroeder phases for low crest factor with a flat amplitude spectrum
+    #[default]
+    Schroeder,
+    /// Uniformly distributed random phases from the given seed
+    Random(u64),
+}
+
+/// Multisine tone parameters
+///
+/// Computes [`MultiTone`] parameters for tones at bins of a period of
+/// `period` samples, with equal amplitude and phases chosen to keep the
+/// crest factor low. The Schroeder phases of tone `k` (counting from zero) are
+/// `-pi*k*(k + 1)/N`. The crest factor of a Schroeder multisine with
+/// consecutive bins is about 1.7 compared to `sqrt(2*N)` for zero phases.
+///
+/// The excitation is exactly periodic if `period` is a power of two.
+///
+/// # Args
+/// * `bins`: Frequency bins, tone frequency is `bin/period` of the sample rate
+/// * `period`: Multisine period in samples
+/// * `amplitude`: Amplitude of each tone
+/// * `phase`: Phase selection
+///
+/// # Returns
+/// Tone parameters
+///
+/// ```
+/// # use idsp::{multisine, MultiTone, MultisinePhase};
+/// let k = multisine(&[1, 2, 3], 64, 1 << 28, MultisinePhase::Schroeder);
+/// assert_eq!(k[1].frequency, 1 << 27);
+/// assert_eq!(k.map(|k| k.phase), [0, -1431655765, 0]); // -1/3 turn
+/// let mut m = MultiTone::default();
+/// let y: Vec<_> = (0..128).map(|_| m.update(&k)).collect();
+/// assert_eq!(y[..64], y[64..]);
+/// ```
+pub fn multisine<const N: usize>(
+    bins: &[u32; N],
+    period: u32,
+    amplitude: i32,
+    phase: MultisinePhase,
+) -> [Tone; N] {
+    let mut rng = match phase {
+        MultisinePhase::Random(seed) => Some(Xoshiro128::new(seed)),
+        MultisinePhase::Schroeder => None,
+    };
+    let mut k = 0;
+    bins.map(|bin| {
+        let phase = match rng.as_mut() {
+            Some(rng) => rng.next_u32() as i32,
+            None => {
+                // -k*(k + 1)/(2*N) turns
+                let n = 2 * N as u64;
+                let p = ((k * (k + 1)) as u64 % n) << 32;
+                (p / n) as u32 as i32
+            }
+            .wrapping_neg(),
+        };
+        k += 1;
+        Tone {
+            frequency: (((bin as u64) << 32) / period as u64) as u32 as i32,
+            phase,
+            amplitude,
+        }
+    })
+}
+
+/// Square wave
+///
+/// Maps a phase (full turn is `1 << 32`) to a square wave that is high
+/// (`i32::MAX`) for a fraction `duty` of the period, centered at phase zero
+/// (like `cos()`), and low (`i32::MIN`) otherwise.
+///
+/// # Args
+/// * `phase`: Phase
+/// * `duty`: Duty cycle in units of `1 << 32`
+///
+/// ```
+/// # use idsp::square;
+/// let y: Vec<_> = (0..8).map(|i| square(i << 29, 1 << 30) > 0).collect();
+/// assert_eq!(y, [true, false, false, false, false, false, false, true]);
+/// ```
+pub fn square(phase: i32, duty: u32) -> i32 {
+    if (phase as u32).wrapping_add(duty >> 1) < duty {
+        i32::MAX
+    } else {
+        i32::MIN
+    }
+}
+
+/// Triangle wave
+///
+/// Maps a phase (full turn is `1 << 32`) to a full scale triangle wave with
+/// the maximum at phase zero and the minimum at `i32::MIN` (like `cos()`).
+///
+/// ```
+/// # use idsp::triangle;
+/// assert_eq!(triangle(0), i32::MAX);
+/// assert_eq!(triangle(1 << 30), 0);
+/// assert_eq!(triangle(-1 << 30), 0);
+/// assert_eq!(triangle(i32::MIN), i32::MIN);
+/// ```
+pub fn triangle(phase: i32) -> i32 {
+    ((1i64 << 31) - 2 * phase.unsigned_abs() as i64).clamp(i32::MIN as _, i32::MAX as _) as _
+}
+
+/// Sawtooth wave
+///
+/// Maps a phase (full turn is `1 << 32`) to a full scale rising
+/// sawtooth wave with the discontinuity at `i32::MIN`.
+/// This is the phase itself.
+///
+/// ```
+/// # use idsp::sawtooth;
+/// assert_eq!(sawtooth(1 << 30), 1 << 30);
+/// ```
+pub fn sawtooth(phase: i32) -> i32 {
+    phase
+}
+
+/// [`Sweep`] configuration
+///
+/// Constructed with [`SweepConfig::linear()`] or [`SweepConfig::exponential()`].
+/// There is no `Default` as the sweep length must be non-zero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SweepConfig {
+    /// Start frequency
+    start: i32,
+    /// Sweep length in samples
+    length: u32,
+    /// Frequency step (linear) or relative increment (exponential),
+    /// in units of `1 << 32`
+    step: i64,
+    exponential: bool,
+}
+
+impl SweepConfig {
+    /// Linear sweep
+    ///
+    /// # Args
+    /// * `start`: Start frequency, a full turn per sample is `1 << 32`
+    /// * `stop`: Stop frequency
+    /// * `length`: Sweep length in samples, `length > 0`
+    pub fn linear(start: i32, stop: i32, length: u32) -> Self {
+        debug_assert!(length > 0);
+        Self {
+            start,
+            length,
+            step: ((stop as i64 - start as i64) << 32) / length as i64,
+            exponential: false,
+        }
+    }
+
+    /// Exponential (logarithmic) sweep
+    ///
+    /// # Args
+    /// * `start`: Start frequency, a full turn per sample is `1 << 32`,
+    ///   `start > 0`
+    /// * `stop`: Stop frequency, `stop > 0`
+    /// * `length`: Sweep length in samples, `length > 0`
+    pub fn exponential(start: i32, stop: i32, length: u32) -> Self {
+        debug_assert!(start > 0 && stop > 0 && length > 0);
+        let r = Float::ln(stop as f64 / start as f64) / length as f64;
+        Self {
+            start,
+            length,
+            step: (Float::exp_m1(r) * (1u64 << 32) as f64) as i64,
+            exponential: true,
+        }
+    }
+}
+
+/// Swept sine generator
+///
+/// A cosine with linearly or exponentially swept frequency (chirp).
+/// The sweep repeats, starting at phase zero, every `length` samples.
+/// A trigger output marks the first sample of each sweep.
+///
+/// ```
+/// # use idsp::{Sweep, SweepConfig};
+/// let k = SweepConfig::exponential(1 << 20, 1 << 30, 1000);
+/// let mut s = Sweep::default();
+/// let mut t = 0;
+/// for i in 0..2500 {
+///     let (_y, trigger) = s.update(&k);
+///     if trigger {
+///         assert_eq!(i % 1000, 0);
+///         t += 1;
+///     }
+///     if i == 999 {
+///         assert!(((s.frequency() >> 32) - (1 << 30)).abs() < 1 << 22);
+///     }
+/// }
+/// assert_eq!(t, 3);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sweep {
+    phase: i32,
+    /// Frequency with 32 fractional bits
+    frequency: i64,
+    /// Samples since the start of the sweep
+    index: u32,
+}
+
+impl Sweep {
+    /// Compute the next output sample.
+    ///
+    /// # Returns
+    /// The output sample and whether this is the first sample of a sweep
+    pub fn update(&mut self, k: &SweepConfig) -> (i32, bool) {
+        let trigger = self.index == 0;
+        if trigger {
+            self.phase = 0;
+            self.frequency = (k.start as i64) << 32;
+        }
+        let y = cossin(self.phase).0;
+        self.phase = self.phase.wrapping_add((self.frequency >> 32) as i32);
+        self.frequency += if k.exponential {
+            ((self.frequency as i128 * k.step as i128) >> 32) as i64
+        } else {
+            k.step
+        };
+        self.index += 1;
+        if self.index == k.length {
+            self.index = 0;
+        }
+        (y, trigger)
+    }
+
+    /// The current frequency in units of `1 << 32` of the sample rate
+    /// with 32 fractional bits
+    pub fn frequency(&self) -> i64 {
+        self.frequency
+    }
+
+    /// Restart the sweep.
+    pub fn reset(&mut self) {
+        self.index = 0;
+    }
+}
+
+/// Interpolation method
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Nearest (preceding) sample
+    #[default]
+    Nearest,
+    /// Linear interpolation
+    Linear,
+    /// Cubic Lagrange interpolation, see [`crate::fir::Farrow`]
+    Cubic,
+}
+
+/// [`LutPlayer`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LutConfig {
+    /// Table samples advanced per output sample with 32 fractional bits
+    pub rate: u64,
+    /// Interpolation between table samples
+    pub interpolation: Interpolation,
+    /// Loop (`true`) or play once (`false`)
+    pub repeat: bool,
+}
+
+/// Arbitrary waveform lookup table player
+///
+/// Plays a caller-provided table of samples at a fractional rate with
+/// interpolation, either once or repeatedly.
+/// In loop mode the interpolation wraps around the table end.
+/// In one-shot mode the first and last samples are repeated for the
+/// interpolation at the table boundaries.
+///
+/// ```
+/// # use idsp::{Interpolation, LutConfig, LutPlayer};
+/// let table = [0, 100, 200, 100];
+/// let k = LutConfig {
+///     rate: 1 << 31, // half speed
+///     interpolation: Interpolation::Linear,
+///     repeat: false,
+/// };
+/// let mut p = LutPlayer::default();
+/// let y: Vec<_> = core::iter::from_fn(|| p.update(&table, &k)).collect();
+/// assert_eq!(y, [0, 50, 100, 150, 200, 150, 100, 100]);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LutPlayer {
+    /// Table position with 32 fractional bits
+    position: u64,
+}
+
+impl LutPlayer {
+    /// Compute the next output sample.
+    ///
+    /// # Args
+    /// * `table`: Samples, non-empty
+    /// * `k`: Configuration
+    ///
+    /// # Returns
+    /// The output sample or `None` if the end of the table has been reached
+    /// in one-shot mode.
+    pub fn update(&mut self, table: &[i32], k: &LutConfig) -> Option<i32> {
+        let n = table.len();
+        let mut i = (self.position >> 32) as usize;
+        if i >= n {
+            if !k.repeat {
+                return None;
+            }
+            i %= n;
+            self.position = ((i as u64) << 32) | (self.position & 0xffff_ffff);
+        }
+        let t = |j: isize| {
+            let j = i as isize + j;
+            table[if k.repeat {
+                j.rem_euclid(n as isize) as usize
+            } else {
+                j.clamp(0, n as isize - 1) as usize
+            }]
+        };
+        let mu = self.position as u32;
+        let y = match k.interpolation {
+            Interpolation::Nearest => t(0),
+            Interpolation::Linear => {
+                let (y0, y1) = (t(0) as i64, t(1) as i64);
+                (y0 + (((y1 - y0) as i128 * mu as i128) >> 32) as i64) as i32
+            }
+            Interpolation::Cubic => Farrow::new([t(-1), t(0), t(1), t(2)]).interpolate(mu),
+        };
+        self.position += k.rate;
+        Some(y)
+    }
+
+    /// The current table position with 32 fractional bits
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Restart at the given table position.
+    pub fn set_position(&mut self, position: u64) {
+        self.position = position;
+    }
+}
+
+/// [`Pulse`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PulseConfig {
+    /// Pulse period in samples, `period > 0`
+    pub period: u32,
+    /// Pulse width in samples
+    pub width: u32,
+    /// Number of pulses per burst, `0` for a continuous pulse train
+    pub count: u32,
+    /// Pulse amplitude
+    pub amplitude: i32,
+}
+
+/// Pulse and burst generator
+///
+/// Emits rectangular pulses of `amplitude` and `width` samples every `period`
+/// samples, zero otherwise. In burst mode the generator stops after
+/// `count` pulses until it is triggered again.
+///
+/// ```
+/// # use idsp::{Pulse, PulseConfig};
+/// let k = PulseConfig { period: 3, width: 1, count: 2, amplitude: 5 };
+/// let mut p = Pulse::default();
+/// let y: Vec<_> = (0..8).map(|_| p.update(&k)).collect();
+/// assert_eq!(y, [5, 0, 0, 5, 0, 0, 0, 0]);
+/// assert!(p.done(&k));
+/// p.trigger();
+/// assert_eq!(p.update(&k), 5);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pulse {
+    /// Sample index within the period
+    index: u32,
+    /// Number of completed pulse periods
+    pulses: u32,
+}
+
+impl Pulse {
+    /// Compute the next output sample.
+    pub fn update(&mut self, k: &PulseConfig) -> i32 {
+        if self.done(k) {
+            return 0;
+        }
+        let y = if self.index < k.width { k.amplitude } else { 0 };
+        self.index += 1;
+        if self.index >= k.period {
+            self.index = 0;
+            self.pulses = self.pulses.saturating_add(1);
+        }
+        y
+    }
+
+    /// Whether the burst is complete
+    pub fn done(&self, k: &PulseConfig) -> bool {
+        k.count != 0 && self.pulses >= k.count
+    }
+
+    /// Start a new burst (or restart the pulse train).
+    pub fn trigger(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// [`Ramp`] configuration
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RampConfig {
+    /// Start value
+    pub start: i32,
+    /// Stop value
+    pub stop: i32,
+    /// Ramp rate (absolute value) in LSB per sample with 32 fractional bits
+    pub rate: u64,
+    /// Hold at `stop` once reached instead of restarting at `start`
+    pub hold: bool,
+}
+
+/// Linear ramp generator
+///
+/// Ramps linearly from `start` to `stop`. Once `stop` is reached the
+/// ramp either holds until it is triggered again or restarts
+/// at `start` (a sawtooth scan).
+///
+/// ```
+/// # use idsp::{Ramp, RampConfig};
+/// let k = RampConfig { start: 10, stop: 4, rate: 5 << 31, hold: true };
+/// let mut r = Ramp::default();
+/// let y: Vec<_> = (0..5).map(|_| r.update(&k)).collect();
+/// assert_eq!(y, [10, 8, 5, 4, 4]);
+/// assert!(r.done(&k));
+/// r.trigger();
+/// assert_eq!(r.update(&k), 10);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Ramp {
+    /// Distance from start with 32 fractional bits
+    offset: u64,
+}
+
+impl Ramp {
+    fn end(k: &RampConfig) -> u64 {
+        (k.stop as i64 - k.start as i64).unsigned_abs() << 32
+    }
+
+    /// Compute the next output sample.
+    pub fn update(&mut self, k: &RampConfig) -> i32 {
+        let end = Self::end(k);
+        let x = (self.offset.min(end) >> 32) as i32;
+        let y = if k.stop >= k.start {
+            k.start.wrapping_add(x)
+        } else {
+            k.start.wrapping_sub(x)
+        };
+        if self.offset < end {
+            self.offset = self.offset.saturating_add(k.rate);
+        } else if !k.hold {
+            self.offset = 0;
+        }
+        y
+    }
+
+    /// Whether the ramp has reached `stop`
+    pub fn done(&self, k: &RampConfig) -> bool {
+        self.offset >= Self::end(k)
+    }
+
+    /// Restart the ramp at `start`.
+    pub fn trigger(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Step sequence generator
+///
+/// Emits a sequence of levels, each held for its own duration, either
+/// cyclically in order or in random order.
+/// Use for step response based system identification.
+///
+/// ```
+/// # use idsp::StepSequence;
+/// let steps = [(5, 2), (-3, 1), (0, 3)];
+/// let mut s = StepSequence::new(1);
+/// let y: Vec<_> = (0..8).map(|_| s.update(&steps, false)).collect();
+/// assert_eq!(y, [5, 5, -3, 0, 0, 0, 5, 5]);
+/// assert_eq!(s.step(), 1);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StepSequence {
+    rng: Xoshiro128,
+    /// Current step index
+    step: usize,
+    /// Sample index within the current step
+    count: u32,
+}
+
+impl StepSequence {
+    /// Create a new step sequence.
+    ///
+    /// # Args
+    /// * `seed`: Random number generator seed for the random order
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Xoshiro128::new(seed),
+            step: 0,
+            count: 0,
+        }
+    }
+
+    /// Compute the next output sample.
+    ///
+    /// # Args
+    /// * `steps`: Non-empty sequence of `(level, duration)` pairs,
+    ///   durations in samples, `duration > 0`
+    /// * `shuffle`: Pick the next step uniformly at random instead of in order
+    ///
+    /// # Returns
+    /// The current level
+    pub fn update(&mut self, steps: &[(i32, u32)], shuffle: bool) -> i32 {
+        let (level, duration) = steps[self.step];
+        self.count += 1;
+        if self.count >= duration {
+            self.count = 0;
+            self.step = if shuffle {
+                ((self.rng.next_u32() as u64 * steps.len() as u64) >> 32) as usize
+            } else {
+                (self.step + 1) % steps.len()
+            };
+        }
+        level
+    }
+
+    /// The index of the current step
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Restart the sequence at the first step.
+    pub fn reset(&mut self) {
+        self.step = 0;
+        self.count = 0;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lut_cubic_loop() {
+        const N: usize = 32;
+        let table: [i32; N] =
+            core::array::from_fn(|i| cossin(((i << 32) / N) as u32 as i32).0 >> 1);
+        let k = LutConfig {
+            rate: (1 << 32) / 4,
+            interpolation: Interpolation::Cubic,
+            repeat: true,
+        };
+        let mut p = LutPlayer::default();
+        for i in 0..4 * 3 * N {
+            let y = p.update(&table, &k).unwrap();
+            let y0 = cossin(((i << 32) / (4 * N)) as u32 as i32).0 >> 1;
+            assert!((y - y0).abs() < 1 << 18, "{i} {y} {y0}");
+        }
+    }
+
+    #[test]
+    fn lut_linear_full_scale() {
+        let table = [i32::MIN, i32::MAX];
+        let k = LutConfig {
+            rate: 3 << 30,
+            interpolation: Interpolation::Linear,
+            repeat: true,
+        };
+        let mut p = LutPlayer::default();
+        for i in 0..16u64 {
+            let x = (i * k.rate) as u32 as f64 / (1u64 << 32) as f64;
+            let (y0, y1) = match ((i * k.rate) >> 32) % 2 {
+                0 => (i32::MIN as f64, i32::MAX as f64),
+                _ => (i32::MAX as f64, i32::MIN as f64),
+            };
+            let y = p.update(&table, &k).unwrap();
+            assert!((y as f64 - (y0 + (y1 - y0) * x)).abs() <= 1.0, "{i} {y}");
+        }
+    }
+
+    #[test]
+    fn multisine_crest() {
+        const N: usize = 32;
+        let bins: [u32; N] = core::array::from_fn(|i| i as u32 + 1);
+        for (phase, crest) in [
+            (MultisinePhase::Schroeder, 2.0),
+            (MultisinePhase::Random(7), 3.5),
+        ] {
+            let k = multisine(&bins, 1 << 10, 1 << 25, phase);
+            let mut m = MultiTone::default();
+            let y: Vec<_> = (0..1 << 10).map(|_| m.update(&k) as f64).collect();
+            let peak = y.iter().fold(0.0f64, |p, y| p.max(y.abs()));
+            let rms = (y.iter().map(|y| y * y).sum::<f64>() / y.len() as f64).sqrt();
+            assert!((rms / (1 << 25) as f64 - (N as f64 / 2.0).sqrt()).abs() < 1e-3);
+            assert!(peak / rms < crest, "{phase:?} {}", peak / rms);
+        }
+    }
+
+    #[test]
+    fn ramp_full_scale() {
+        let k = RampConfig {
+            start: i32::MIN,
+            stop: i32::MAX,
+            rate: 1 << 63,
+            hold: false,
+        };
+        let mut r = Ramp::default();
+        let y: Vec<_> = (0..8).map(|_| r.update(&k)).collect();
+        assert_eq!(
+            y,
+            [i32::MIN, 0, i32::MAX, i32::MIN, 0, i32::MAX, i32::MIN, 0]
+        );
+    }
+}
@@ -1,3 +1,4 @@
+//! Test helpers
 #![allow(dead_code)]
 use super::Complex;
 
@@ -7,7 +8,7 @@ use super::Complex;
 /// # Args
 /// * `a` - First input.
 /// * `b` - Second input. The relative tolerance is computed with respect to the maximum of the
-/// absolute values of the first and second inputs.
+///   absolute values of the first and second inputs.
 /// * `rtol` - Relative tolerance.
 /// * `atol` - Fixed tolerance.
 ///
@@ -17,18 +18,22 @@ pub fn max_error(a: f64, b: f64, rtol: f64, atol: f64) -> f64 {
     rtol * a.abs().max(b.abs()) + atol
 }
 
+/// Whether two values are equal within fixed and relative tolerances
 pub fn isclose(a: f64, b: f64, rtol: f64, atol: f64) -> bool {
     (a - b).abs() <= a.abs().max(b.abs()) * rtol + atol
 }
 
+/// Whether two values are equal within fixed and relative tolerances
 pub fn isclosef(a: f32, b: f32, rtol: f32, atol: f32) -> bool {
     (a - b).abs() <= a.abs().max(b.abs()) * rtol + atol
 }
 
+/// Whether two complex values are equal within fixed and relative tolerances
 pub fn complex_isclose(a: Complex<f32>, b: Complex<f32>, rtol: f32, atol: f32) -> bool {
     isclosef(a.re, b.re, rtol, atol) && isclosef(a.im, b.im, rtol, atol)
 }
 
+/// Whether two slices of complex values are equal within fixed and relative tolerances
 pub fn complex_allclose(a: &[Complex<f32>], b: &[Complex<f32>], rtol: f32, atol: f32) -> bool {
     a.iter()
         .zip(b)
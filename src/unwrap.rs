@@ -54,7 +54,27 @@ pub fn saturating_scale(lo: i32, hi: i32, shift: u32) -> i32 {
 ///
 /// This is unwrapping as in the phase and overflow unwrapping context, not
 /// unwrapping as in the `Result`/`Option` context.
-#[derive(Copy, Clone, Default, Deserialize, Serialize)]
+///
+/// The output type `Q` is wider than the input type: `Unwrapper<i64>`
+/// extends i32 phase into i64, `Unwrapper<i128>` extends i64 phase
+/// (e.g. the integrated output of a low gain PLL) into i128.
+///
+/// ```
+/// # use idsp::Unwrapper;
+/// let mut u = Unwrapper::<i64>::default();
+/// for x in (0..10i64).map(|i| i * (3 << 29)) {
+///     u.update(x as i32);
+/// }
+/// assert_eq!(u.y(), 27 << 29);
+///
+/// let mut u = Unwrapper::<i128>::default();
+/// for x in (0..10i128).map(|i| i * (3 << 61)) {
+///     u.update(x as i64);
+/// }
+/// assert_eq!(u.y(), 27 << 61);
+/// assert_eq!(u.phase::<i64>(), 3 << 61);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Unwrapper<Q> {
     /// current output
     y: Q,
@@ -81,15 +101,61 @@ where
         dx
     }
 
+    /// Account for a known phase advance, e.g. during missed samples.
+    ///
+    /// Add `dy` (e.g. `n` missed samples times the frequency) to the output so that
+    /// the next [`Unwrapper::update()`] resolves wraps relative to the predicted phase.
+    ///
+    /// ```
+    /// # use idsp::Unwrapper;
+    /// let mut u = Unwrapper::<i64>::default();
+    /// let f = 3 << 29; // 3/8 turn per sample
+    /// u.update(0i32);
+    /// // Three missed samples
+    /// u.advance(3 * f as i64);
+    /// u.update((4 * f as i64) as i32);
+    /// assert_eq!(u.y(), 4 * f as i64);
+    /// ```
+    pub fn advance(&mut self, dy: Q) {
+        self.y = self.y.wrapping_add(&dy);
+    }
+
     /// The current number of wraps
+    ///
+    /// Rounded to nearest: this is consistent with [`Unwrapper::split()`] for
+    /// a signed phase type of `S` bits.
     pub fn wraps<P, const S: u32>(&self) -> P
     where
         Q: AsPrimitive<P> + Shr<u32, Output = Q>,
-        P: 'static + Copy + WrappingAdd + Signed + BitAnd<u32, Output = P>,
+        P: 'static + Copy + WrappingAdd + Signed + BitAnd<Output = P>,
     {
         (self.y >> S)
             .as_()
-            .wrapping_add(&((self.y >> (S - 1)).as_() & 1))
+            .wrapping_add(&((self.y >> (S - 1)).as_() & P::one()))
+    }
+
+    /// The current output split into number of wraps and phase
+    ///
+    /// The output is `wraps*(1 << bits) + phase` where `bits` is the width of
+    /// the phase type `P`.
+    ///
+    /// ```
+    /// # use idsp::Unwrapper;
+    /// let mut u = Unwrapper::<i64>::default();
+    /// u.seed((-5 << 32) + (3 << 30));
+    /// assert_eq!(u.split::<i32>(), (-4, -1 << 30));
+    /// assert_eq!(u.wraps::<i64, 32>(), -4);
+    /// u.reset();
+    /// assert_eq!(u.y(), 0);
+    /// ```
+    pub fn split<P>(&self) -> (Q, P)
+    where
+        P: 'static + Copy + AsPrimitive<Q>,
+        Q: AsPrimitive<P> + WrappingSub + Shr<u32, Output = Q>,
+    {
+        let p: P = self.y.as_();
+        let bits = 8 * core::mem::size_of::<P>() as u32;
+        (self.y.wrapping_sub(&p.as_()) >> bits, p)
     }
 
     /// The current phase
@@ -105,6 +171,82 @@ where
     pub fn y(&self) -> Q {
         self.y
     }
+
+    /// Set the output including wraps, e.g. to resume from a checkpoint.
+    pub fn seed(&mut self, y: Q) {
+        self.y = y;
+    }
+
+    /// Reset the output and the number of wraps to zero.
+    pub fn reset(&mut self)
+    where
+        Q: Zero,
+    {
+        self.y = Q::zero();
+    }
+}
+
+/// 32 to 64 bit timestamp extender
+///
+/// Extends a free running, wrapping 32 bit hardware timestamp (counter)
+/// into a monotonic 64 bit timestamp.
+///
+/// ```
+/// # use idsp::TimestampExtender;
+/// let mut t = TimestampExtender::default();
+/// assert_eq!(t.update(0xffff_fff0), 0xffff_fff0);
+/// assert_eq!(t.update(0x10), 0x1_0000_0010);
+/// // Two and a half wraps elapsed according to a coarse clock
+/// assert_eq!(t.update_near(0x20, 5 << 31), 0x3_0000_0020);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TimestampExtender {
+    t: u64,
+}
+
+impl TimestampExtender {
+    /// Extend a new timestamp.
+    ///
+    /// Assumes that less than `1 << 32` counts elapsed since the last
+    /// timestamp.
+    ///
+    /// # Args
+    /// * `x`: New 32 bit timestamp
+    ///
+    /// # Returns
+    /// The extended 64 bit timestamp
+    pub fn update(&mut self, x: u32) -> u64 {
+        self.t = self.t.wrapping_add(x.wrapping_sub(self.t as u32) as u64);
+        self.t
+    }
+
+    /// Extend a new timestamp given a coarse estimate of elapsed counts.
+    ///
+    /// This resolves multiple wraps between timestamps.
+    /// The extended timestamp is the one closest to the last timestamp plus
+    /// `elapsed`. The estimate must be accurate to better than `1 << 31` counts.
+    ///
+    /// # Args
+    /// * `x`: New 32 bit timestamp
+    /// * `elapsed`: Estimate of the counts elapsed since the last timestamp
+    ///
+    /// # Returns
+    /// The extended 64 bit timestamp
+    pub fn update_near(&mut self, x: u32, elapsed: u64) -> u64 {
+        let t = self.t.wrapping_add(elapsed);
+        self.t = t.wrapping_add(x.wrapping_sub(t as u32) as i32 as u64);
+        self.t
+    }
+
+    /// The current extended timestamp
+    pub fn get(&self) -> u64 {
+        self.t
+    }
+
+    /// Set the extended timestamp.
+    pub fn set(&mut self, t: u64) {
+        self.t = t;
+    }
 }
 
 #[cfg(test)]
@@ -153,7 +295,7 @@ mod tests {
             (0x100, 0, 1),
             (-1 << 31, 0, -1 << 23),
             (0x7fffffff, 0, 0x007f_ffff),
-            (0x7fffffff, 1, 0x0017f_ffff),
+            (0x7fffffff, 1, 0x017f_ffff),
             (-0x7fffffff, -1, -0x0180_0000),
             (0x1234_5600, 0x7f, 0x7f12_3456),
             (0x1234_5600, -0x7f, -0x7f00_0000 + 0x12_3456),
@@ -0,0 +1,161 @@
+use crate::{fft, Complex};
+
+/// Welch power spectral density estimator
+///
+/// Streaming input samples are cut into segments of length `N` with a
+/// configurable hop (`hop < N` for overlapping segments), windowed,
+/// transformed with [`fft()`] and the one-sided power spectra are
+/// accumulated.
+///
+/// All buffers are provided by the caller:
+/// * `window`: Window of length `N` (a power of two) in `i32`
+///   [`crate::Coefficient`] scaling (e.g. from [`crate::window::hann()`]
+///   with `periodic = true`)
+/// * `segment`: Input segment buffer of length `N`
+/// * `scratch`: FFT buffer of length `N`
+/// * `sum`: Power spectrum accumulator of length `N/2 + 1`
+///
+/// ```
+/// # use idsp::{cossin, window::hann, Complex, Welch};
+/// const N: usize = 64;
+/// let window: [i32; N] = hann(true);
+/// let mut segment = [0; N];
+/// let mut scratch = [Complex::new(0, 0); N];
+/// let mut sum = [0.0; N / 2 + 1];
+/// let mut w = Welch::new(&window, &mut segment, &mut scratch, &mut sum, N / 2);
+/// for i in 0..1024 {
+///     // Amplitude 1/2 full scale at bin 8
+///     w.update(cossin((i as i32) << 29).0 >> 1);
+/// }
+/// assert_eq!(w.count(), 31);
+/// let mut psd = [0.0; N / 2 + 1];
+/// w.psd(&mut psd);
+/// let peak = psd.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap();
+/// assert_eq!(peak.0, 8);
+/// // Parseval: total power A**2/2
+/// let p: f32 = psd.iter().sum::<f32>() / N as f32;
+/// assert!((p / (1u64 << 59) as f32 - 1.0).abs() < 1e-3);
+/// ```
+pub struct Welch<'a> {
+    window: &'a [i32],
+    segment: &'a mut [i32],
+    scratch: &'a mut [Complex<i32>],
+    sum: &'a mut [f32],
+    hop: usize,
+    fill: usize,
+    count: u32,
+}
+
+impl<'a> Welch<'a> {
+    /// Create a new estimator
+    ///
+    /// # Args
+    /// * `window`: Window
+    /// * `segment`: Segment buffer
+    /// * `scratch`: FFT buffer
+    /// * `sum`: Power spectrum accumulator
+    /// * `hop`: Number of samples between segment starts, `0 < hop <= N`
+    pub fn new(
+        window: &'a [i32],
+        segment: &'a mut [i32],
+        scratch: &'a mut [Complex<i32>],
+        sum: &'a mut [f32],
+        hop: usize,
+    ) -> Self {
+        let n = window.len();
+        debug_assert!(n.is_power_of_two());
+        debug_assert_eq!(segment.len(), n);
+        debug_assert_eq!(scratch.len(), n);
+        debug_assert_eq!(sum.len(), n / 2 + 1);
+        debug_assert!(hop > 0 && hop <= n);
+        sum.fill(0.0);
+        Self {
+            window,
+            segment,
+            scratch,
+            sum,
+            hop,
+            fill: 0,
+            count: 0,
+        }
+    }
+
+    /// Ingest a new sample.
+    ///
+    /// # Returns
+    /// Whether a segment was completed and accumulated.
+    pub fn update(&mut self, x: i32) -> bool {
+        self.segment[self.fill] = x;
+        self.fill += 1;
+        if self.fill < self.segment.len() {
+            return false;
+        }
+        for ((y, x), w) in self
+            .scratch
+            .iter_mut()
+            .zip(self.segment.iter())
+            .zip(self.window.iter())
+        {
+            *y = Complex::new(((*x as i64 * *w as i64) >> 30) as i32, 0);
+        }
+        let e = fft(self.scratch);
+        let scale = (1u64 << (2 * e)) as f32;
+        for (s, y) in self.sum.iter_mut().zip(self.scratch.iter()) {
+            let p = (y.re as i64 * y.re as i64) as u64 + (y.im as i64 * y.im as i64) as u64;
+            *s += p as f32 * scale;
+        }
+        self.count += 1;
+        self.segment.copy_within(self.hop.., 0);
+        self.fill -= self.hop;
+        true
+    }
+
+    /// Process a block of samples.
+    pub fn process(&mut self, x: &[i32]) {
+        for x in x.iter() {
+            self.update(*x);
+        }
+    }
+
+    /// Number of segments accumulated
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Accumulated power spectra `|DFT(window*x)|**2`
+    pub fn sum(&self) -> &[f32] {
+        self.sum
+    }
+
+    /// Compute the averaged one-sided power spectral density.
+    ///
+    /// The PSD is normalized to the sample rate and the window power:
+    /// `psd.iter().sum::<f32>()/N` is the mean signal power.
+    ///
+    /// # Args
+    /// * `psd`: Output, length `N/2 + 1`, in units of the input squared per
+    ///   sample rate
+    pub fn psd(&self, psd: &mut [f32]) {
+        let n = self.window.len();
+        let w2: f32 = self
+            .window
+            .iter()
+            .map(|w| {
+                let w = *w as f32 / (1 << 30) as f32;
+                w * w
+            })
+            .sum();
+        let norm = 1.0 / (self.count.max(1) as f32 * w2);
+        for (k, (p, s)) in psd.iter_mut().zip(self.sum.iter()).enumerate() {
+            let c = if k == 0 || k == n / 2 { 1.0 } else { 2.0 };
+            *p = c * norm * s;
+        }
+    }
+
+    /// Reset the accumulator and the segment buffer.
+    pub fn reset(&mut self) {
+        self.sum.fill(0.0);
+        self.count = 0;
+        self.fill = 0;
+    }
+}
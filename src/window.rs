@@ -0,0 +1,141 @@
+//! Window functions
+//!
+//! Window taps are generated in the [`Coefficient`] scaling of the output
+//! type: unity is `Coefficient::ONE` (e.g. `1 << 30` for `i32`).
+//!
+//! Symmetric windows (`periodic = false`) are appropriate for FIR filter
+//! design. Periodic windows (`periodic = true`, the first `N` samples of
+//! the symmetric window of length `N + 1`) are appropriate for spectral
+//! analysis with the FFT.
+
+use core::f64::consts::PI;
+
+use num_traits::{AsPrimitive, Float};
+
+use crate::Coefficient;
+
+fn generate<T, const N: usize>(periodic: bool, f: impl Fn(f64) -> f64) -> [T; N]
+where
+    T: Coefficient + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    let d = if periodic {
+        N
+    } else {
+        N.saturating_sub(1).max(1)
+    };
+    core::array::from_fn(|i| T::quantize(f(i as f64 / d as f64)))
+}
+
+fn cosine_sum<T, const N: usize>(periodic: bool, a: &[f64]) -> [T; N]
+where
+    T: Coefficient + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    generate(periodic, |x| {
+        a.iter()
+            .enumerate()
+            .map(|(k, a)| {
+                let a = if k & 1 == 1 { -a } else { *a };
+                a * Float::cos(2.0 * PI * k as f64 * x)
+            })
+            .sum()
+    })
+}
+
+/// Hann window
+///
+/// ```
+/// # use idsp::window::hann;
+/// assert_eq!(hann::<i32, 5>(false), [0, 1 << 29, 1 << 30, 1 << 29, 0]);
+/// assert_eq!(hann::<i32, 4>(true), [0, 1 << 29, 1 << 30, 1 << 29]);
+/// ```
+pub fn hann<T, const N: usize>(periodic: bool) -> [T; N]
+where
+    T: Coefficient + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    cosine_sum(periodic, &[0.5, 0.5])
+}
+
+/// Hamming window
+pub fn hamming<T, const N: usize>(periodic: bool) -> [T; N]
+where
+    T: Coefficient + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    cosine_sum(periodic, &[0.54, 0.46])
+}
+
+/// Four term Blackman-Harris window
+///
+/// Sidelobes are below -92 dB.
+pub fn blackman_harris<T, const N: usize>(periodic: bool) -> [T; N]
+where
+    T: Coefficient + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    cosine_sum(periodic, &[0.35875, 0.48829, 0.14128, 0.01168])
+}
+
+/// Zeroth order modified Bessel function of the first kind
+fn bessel_i0(x: f64) -> f64 {
+    let q = x * x / 4.0;
+    let mut t = 1.0;
+    let mut s = 1.0;
+    for k in 1..100 {
+        t *= q / (k * k) as f64;
+        s += t;
+        if t < s * 1e-17 {
+            break;
+        }
+    }
+    s
+}
+
+/// Kaiser window
+///
+/// # Args
+/// * `beta`: Shape parameter. Larger values give lower sidelobes
+///   and a wider main lobe. E.g. `beta = 8.6` for about -90 dB sidelobes.
+/// * `periodic`: Periodic window
+///
+/// ```
+/// # use idsp::window::kaiser;
+/// let w = kaiser::<f64, 5>(0.0, false);
+/// assert_eq!(w, [1.0; 5]);
+/// let w = kaiser::<i32, 5>(5.0, false);
+/// assert_eq!(w[2], 1 << 30);
+/// assert_eq!(w[0], w[4]);
+/// ```
+pub fn kaiser<T, const N: usize>(beta: f64, periodic: bool) -> [T; N]
+where
+    T: Coefficient + AsPrimitive<f64>,
+    f64: AsPrimitive<T>,
+{
+    let i0 = bessel_i0(beta);
+    generate(periodic, |x| {
+        let r = 2.0 * x - 1.0;
+        bessel_i0(beta * Float::sqrt(Float::max(1.0 - r * r, 0.0))) / i0
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn symmetric() {
+        let w: [f64; 9] = blackman_harris(false);
+        assert!(w[0].abs() < 1e-4);
+        assert!((w[4] - 1.0).abs() < 1e-12);
+        let w: [f64; 9] = hamming(false);
+        assert!((w[0] - 0.08).abs() < 1e-12);
+        for w in [w, kaiser(8.6, false), hann(false)] {
+            for i in 0..9 {
+                assert!((w[i] - w[8 - i]).abs() < 1e-12);
+            }
+        }
+        assert!((bessel_i0(1.0) - 1.2660658777520082).abs() < 1e-14);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{Delay, Ewma, Filter};
+
+/// Streaming cross-correlation estimator
+///
+/// Estimates `E[x[n]*y[n - lag]]` for `L` configurable lags
+/// `0 <= lag < D` with exponential averaging (see [`Ewma`]).
+/// For negative lags swap the channels.
+///
+/// The products are scaled by `1/(1 << 32)`: two correlated full scale
+/// inputs give a correlation estimate of `1 << 30`, half of full
+/// scale. The scaled product never saturates.
+///
+/// ```
+/// # use idsp::XCorr;
+/// let mut c = XCorr::<8, 3>::default();
+/// let lags = [0, 2, 3];
+/// let mut y = [0; 3];
+/// let mut s = 1u32;
+/// for _ in 0..20_000 {
+///     // Pseudo-random binary y, x is y delayed by 2 samples
+///     s = s.wrapping_mul(1664525).wrapping_add(1013904223);
+///     y.rotate_right(1);
+///     y[0] = if s >> 31 == 1 { 1 << 30 } else { -1 << 30 };
+///     c.update(y[2], y[0], &lags, 1 << 24);
+/// }
+/// let r = c.get();
+/// assert!(r[1] > r[0] && r[1] > r[2]);
+/// assert!((r[1] - (1 << 28)).abs() < 1 << 16);
+/// ```
+#[derive(Clone, Debug)]
+pub struct XCorr<const D: usize, const L: usize> {
+    y: Delay<i32, D>,
+    r: [Ewma; L],
+}
+
+impl<const D: usize, const L: usize> Default for XCorr<D, L> {
+    fn default() -> Self {
+        Self {
+            y: Delay::default(),
+            r: [Ewma::default(); L],
+        }
+    }
+}
+
+impl<const D: usize, const L: usize> XCorr<D, L> {
+    /// Ingest a new pair of samples.
+    ///
+    /// # Args
+    /// * `x`: New sample of the first channel
+    /// * `y`: New sample of the second channel
+    /// * `lags`: Lags in samples, `lag < D`
+    /// * `alpha`: Averaging weight, see [`Ewma`]
+    ///
+    /// # Returns
+    /// The updated correlation estimates
+    pub fn update(&mut self, x: i32, y: i32, lags: &[usize; L], alpha: u32) -> [i32; L] {
+        self.y.push(y);
+        for (r, lag) in self.r.iter_mut().zip(lags.iter()) {
+            let p = (x as i64 * self.y.tap(*lag) as i64) >> 32;
+            r.update(p as i32, &alpha);
+        }
+        self.get()
+    }
+
+    /// The current correlation estimates
+    pub fn get(&self) -> [i32; L] {
+        core::array::from_fn(|i| self.r[i].get())
+    }
+
+    /// Reset the estimates (but not the delay line).
+    pub fn reset(&mut self) {
+        self.r = [Ewma::default(); L];
+    }
+}
+
+/// Streaming autocorrelation estimator
+///
+/// Estimates `E[x[n]*x[n - lag]]` for `L` configurable lags `0 <= lag < D` with
+/// exponential averaging, see [`XCorr`]. Additionally tracks the power
+/// `E[x[n]**2]` to provide normalized autocorrelation coefficients.
+///
+/// Large positive or negative coefficients at short lags in a closed loop
+/// error signal indicate oscillation or marginal stability.
+///
+/// ```
+/// # use idsp::AutoCorr;
+/// let mut c = AutoCorr::<4, 2>::default();
+/// for i in 0..1000 {
+///     // Oscillation at half the sampling rate
+///     let x = if i & 1 == 0 { 1 << 30 } else { -1 << 30 };
+///     c.update(x, &[1, 2], 1 << 26);
+/// }
+/// let r = c.coefficients();
+/// assert!((r[0] - i32::MIN).abs() < 1 << 16);
+/// assert!((r[1] - i32::MAX).abs() < 1 << 16);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AutoCorr<const D: usize, const L: usize> {
+    xcorr: XCorr<D, L>,
+    power: Ewma,
+}
+
+impl<const D: usize, const L: usize> Default for AutoCorr<D, L> {
+    fn default() -> Self {
+        Self {
+            xcorr: XCorr::default(),
+            power: Ewma::default(),
+        }
+    }
+}
+
+impl<const D: usize, const L: usize> AutoCorr<D, L> {
+    /// Ingest a new sample.
+    ///
+    /// # Args
+    /// * `x`: New sample
+    /// * `lags`: Lags in samples, `lag < D`
+    /// * `alpha`: Averaging weight, see [`Ewma`]
+    ///
+    /// # Returns
+    /// The updated autocorrelation estimates
+    pub fn update(&mut self, x: i32, lags: &[usize; L], alpha: u32) -> [i32; L] {
+        self.power
+            .update(((x as i64 * x as i64) >> 32) as i32, &alpha);
+        self.xcorr.update(x, x, lags, alpha)
+    }
+
+    /// The current autocorrelation estimates
+    pub fn get(&self) -> [i32; L] {
+        self.xcorr.get()
+    }
+
+    /// The current power estimate (autocorrelation at zero lag)
+    pub fn power(&self) -> i32 {
+        self.power.get()
+    }
+
+    /// The normalized autocorrelation coefficients
+    ///
+    /// The autocorrelation divided by the power, full scale is
+    /// `[-1, 1)`, saturating. Zero if the power is zero.
+    pub fn coefficients(&self) -> [i32; L] {
+        let p = self.power() as i64;
+        self.get().map(|r| {
+            if p == 0 {
+                0
+            } else {
+                (((r as i64) << 31) / p).clamp(i32::MIN as _, i32::MAX as _) as _
+            }
+        })
+    }
+
+    /// Reset the estimates (but not the delay line).
+    pub fn reset(&mut self) {
+        self.xcorr.reset();
+        self.power = Ewma::default();
+    }
+}